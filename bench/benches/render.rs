@@ -0,0 +1,220 @@
+//! Benchmarks for looking up and drawing glyphs.
+//!
+//! Run with `cargo bench --manifest-path bench/Cargo.toml`. Each benchmark is run with ASCII and
+//! Latin-1 text, which use the two ranges of `GLYPH_MAPPING`.
+
+use std::convert::Infallible;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use embedded_graphics::{
+    mono_font::{mapping::GlyphMapping, MonoFont, MonoTextStyle},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use profont::*;
+
+const SIZES: [(&str, &MonoFont); 7] = [
+    ("7pt", &PROFONT_7_POINT),
+    ("9pt", &PROFONT_9_POINT),
+    ("10pt", &PROFONT_10_POINT),
+    ("12pt", &PROFONT_12_POINT),
+    ("14pt", &PROFONT_14_POINT),
+    ("18pt", &PROFONT_18_POINT),
+    ("24pt", &PROFONT_24_POINT),
+];
+
+const SHEETS: [GlyphSheet; 7] = [
+    PROFONT_7_POINT_SHEET,
+    PROFONT_9_POINT_SHEET,
+    PROFONT_10_POINT_SHEET,
+    PROFONT_12_POINT_SHEET,
+    PROFONT_14_POINT_SHEET,
+    PROFONT_18_POINT_SHEET,
+    PROFONT_24_POINT_SHEET,
+];
+
+/// Every printable ASCII character, then every character of the Latin-1 supplement.
+fn texts() -> [(&'static str, String); 2] {
+    [
+        ("ascii", (' '..='~').collect()),
+        ("latin1", ('\u{a0}'..='ÿ').collect()),
+    ]
+}
+
+/// A draw target that discards everything drawn to it, to measure the cost of the font alone.
+struct NullDisplay {
+    size: Size,
+}
+
+impl DrawTarget for NullDisplay {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            black_box(pixel);
+        }
+        Ok(())
+    }
+}
+
+impl OriginDimensions for NullDisplay {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+/// A packed 1 bit per pixel framebuffer, like those kept in RAM for monochrome panels.
+struct Framebuffer {
+    size: Size,
+    bytes_per_row: usize,
+    data: Vec<u8>,
+}
+
+impl Framebuffer {
+    fn new(size: Size) -> Self {
+        let bytes_per_row = (size.width as usize).div_ceil(8);
+        Framebuffer {
+            size,
+            bytes_per_row,
+            data: vec![0; bytes_per_row * size.height as usize],
+        }
+    }
+}
+
+impl DrawTarget for Framebuffer {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if point.x < 0
+                || point.y < 0
+                || point.x >= self.size.width as i32
+                || point.y >= self.size.height as i32
+            {
+                continue;
+            }
+            let index = point.y as usize * self.bytes_per_row + point.x as usize / 8;
+            let mask = 0x80 >> (point.x % 8);
+            if color.is_on() {
+                self.data[index] |= mask;
+            } else {
+                self.data[index] &= !mask;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Framebuffer {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+/// Returns the size of a single line of `text`.
+fn line_size(font: &MonoFont, text: &str) -> Size {
+    let chars = text.chars().count() as u32;
+    Size::new(
+        chars * (font.character_size.width + font.character_spacing),
+        font.character_size.height,
+    )
+}
+
+fn glyph_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("glyph_lookup");
+    for (name, text) in texts().iter() {
+        group.throughput(Throughput::Elements(text.chars().count() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), text, |b, text| {
+            b.iter(|| {
+                for ch in black_box(text.as_str()).chars() {
+                    black_box(GLYPH_MAPPING.index(ch));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn draw_null(c: &mut Criterion) {
+    let mut group = c.benchmark_group("draw_null");
+    for (size, font) in SIZES.iter() {
+        for (name, text) in texts().iter() {
+            let mut display = NullDisplay {
+                size: line_size(font, text),
+            };
+            let style = MonoTextStyle::new(font, BinaryColor::On);
+            group.throughput(Throughput::Elements(text.chars().count() as u64));
+            group.bench_with_input(BenchmarkId::new(*size, name), text, |b, text| {
+                b.iter(|| {
+                    Text::with_baseline(black_box(text), Point::zero(), style, Baseline::Top)
+                        .draw(&mut display)
+                        .unwrap()
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn draw_framebuffer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("draw_framebuffer");
+    for (size, font) in SIZES.iter() {
+        for (name, text) in texts().iter() {
+            let mut display = Framebuffer::new(line_size(font, text));
+            let style = MonoTextStyle::new(font, BinaryColor::On);
+            group.throughput(Throughput::Elements(text.chars().count() as u64));
+            group.bench_with_input(BenchmarkId::new(*size, name), text, |b, text| {
+                b.iter(|| {
+                    Text::with_baseline(black_box(text), Point::zero(), style, Baseline::Top)
+                        .draw(&mut display)
+                        .unwrap()
+                })
+            });
+            black_box(&display.data);
+        }
+    }
+    group.finish();
+}
+
+fn blit_framebuffer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("blit_framebuffer");
+    for (sheet, (size, _)) in SHEETS.iter().zip(SIZES.iter()) {
+        for (name, text) in texts().iter() {
+            let line = line_size(sheet.font(), text);
+            let mut buffer = vec![0; (line.width as usize).div_ceil(8) * line.height as usize];
+            let mut framebuffer =
+                framebuffer::Framebuffer::new(&mut buffer, line.width, line.height);
+            group.throughput(Throughput::Elements(text.chars().count() as u64));
+            group.bench_with_input(BenchmarkId::new(*size, name), text, |b, text| {
+                b.iter(|| {
+                    framebuffer.draw_text(
+                        sheet,
+                        black_box(text),
+                        Point::zero(),
+                        framebuffer::BlitMode::Set,
+                    )
+                })
+            });
+            black_box(framebuffer.buffer());
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    glyph_lookup,
+    draw_null,
+    draw_framebuffer,
+    blit_framebuffer
+);
+criterion_main!(benches);
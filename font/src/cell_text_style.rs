@@ -0,0 +1,313 @@
+use core::convert::Infallible;
+
+use embedded_graphics::{
+    image::{Image, ImageDrawableExt},
+    mono_font::{DecorationDimensions, MonoFont, MonoTextStyle},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+        Baseline, DecorationColor,
+    },
+};
+
+/// Glyphs wider than this don't fit in the bit mask of a row, and are drawn by `MonoTextStyle`.
+const MAX_GLYPH_WIDTH: u32 = u32::BITS;
+
+/// A text style that draws each character cell of a ProFont font with a single call.
+///
+/// `MonoTextStyle` draws a glyph, the spacing after it and each decoration with separate calls.
+/// This style combines them, which helps on displays where each call sets up a new window over SPI
+/// or I2C. When the style has a text and a background colour every cell, including the spacing
+/// after it and the underline and strikethrough, is drawn with one `fill_contiguous`. Without one
+/// of them some pixels of the cell are left untouched, so each cell is drawn with one `draw_iter`
+/// instead. Setting `whole_lines` draws each line of text with a single call rather than each cell.
+///
+/// The pixels drawn are the same as those of the wrapped `MonoTextStyle`.
+///
+/// ```rust
+/// use embedded_graphics::{
+///     mock_display::MockDisplay,
+///     mono_font::MonoTextStyleBuilder,
+///     pixelcolor::BinaryColor,
+///     prelude::*,
+///     text::Text,
+/// };
+/// use profont::{CellTextStyle, PROFONT_7_POINT};
+///
+/// # fn main() -> Result<(), core::convert::Infallible> {
+/// let mut display = MockDisplay::new();
+/// let style = MonoTextStyleBuilder::new()
+///     .font(&PROFONT_7_POINT)
+///     .text_color(BinaryColor::On)
+///     .background_color(BinaryColor::Off)
+///     .underline()
+///     .build();
+///
+/// Text::new("Hello", Point::new(0, 7), CellTextStyle::new(style)).draw(&mut display)?;
+/// # Ok(()) }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CellTextStyle<'a, C> {
+    /// The style to draw with.
+    pub style: MonoTextStyle<'a, C>,
+    /// Draw each line of text with one call, instead of each character cell.
+    pub whole_lines: bool,
+}
+
+impl<'a, C: PixelColor> CellTextStyle<'a, C> {
+    /// Creates a style that draws each character cell with a single call.
+    pub const fn new(style: MonoTextStyle<'a, C>) -> Self {
+        CellTextStyle {
+            style,
+            whole_lines: false,
+        }
+    }
+
+    /// Creates a style that draws each line of text with a single call.
+    pub const fn whole_lines(style: MonoTextStyle<'a, C>) -> Self {
+        CellTextStyle {
+            style,
+            whole_lines: true,
+        }
+    }
+
+    /// Returns `true` if the cells contain everything `MonoTextStyle` would draw, which isn't the
+    /// case for fonts with wide glyphs or decorations below the cell.
+    fn fits_in_cells(&self) -> bool {
+        let font = self.style.font;
+        let height = font.character_size.height;
+        (1..=MAX_GLYPH_WIDTH).contains(&font.character_size.width)
+            && (self.underline_color().is_none()
+                || font.underline.offset + font.underline.height <= height)
+            && (self.strikethrough_color().is_none()
+                || font.strikethrough.offset + font.strikethrough.height <= height)
+    }
+
+    fn underline_color(&self) -> Option<C> {
+        decoration_color(self.style.underline_color, self.style.text_color)
+    }
+
+    fn strikethrough_color(&self) -> Option<C> {
+        decoration_color(self.style.strikethrough_color, self.style.text_color)
+    }
+
+    /// Returns the colour of the pixel `x` pixels into the advance of a character, on row `y`, or
+    /// `None` if it is left untouched. `glyph_row` has a bit set for each set pixel of the glyph.
+    fn color(&self, glyph_row: u32, x: u32, y: u32) -> Option<C> {
+        let font = self.style.font;
+        let on_row = |decoration: &DecorationDimensions| {
+            y >= decoration.offset && y < decoration.offset + decoration.height
+        };
+
+        // The underline is drawn after the strikethrough, so it wins where they overlap
+        if let Some(color) = self.underline_color().filter(|_| on_row(&font.underline)) {
+            Some(color)
+        } else if let Some(color) = self
+            .strikethrough_color()
+            .filter(|_| on_row(&font.strikethrough))
+        {
+            Some(color)
+        } else if x < font.character_size.width && glyph_row & (1 << x) != 0 {
+            self.style.text_color
+        } else {
+            self.style.background_color
+        }
+    }
+
+    /// Returns the colours of the first `width` pixels of each row of `text`, in row-major order.
+    fn colors<'t>(&'t self, text: &'t str, width: u32) -> impl Iterator<Item = Option<C>> + 't {
+        let font = self.style.font;
+        let advance = font.character_size.width + font.character_spacing;
+
+        (0..font.character_size.height).flat_map(move |y| {
+            text.chars()
+                .zip((0..width).step_by(advance as usize))
+                .flat_map(move |(ch, x)| {
+                    let row = glyph_row(font, ch, y);
+                    (0..advance.min(width - x)).map(move |column| self.color(row, column, y))
+                })
+        })
+    }
+
+    /// Draws the first `width` pixels of `text`, with its top left corner at `position`.
+    fn draw_span<D>(
+        &self,
+        text: &str,
+        position: Point,
+        width: u32,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        let area = Rectangle::new(
+            position,
+            Size::new(width, self.style.font.character_size.height),
+        );
+
+        match (self.style.text_color, self.style.background_color) {
+            (Some(_), Some(background_color)) => target.fill_contiguous(
+                &area,
+                self.colors(text, width)
+                    .map(|color| color.unwrap_or(background_color)),
+            ),
+            _ => target.draw_iter(
+                area.points()
+                    .zip(self.colors(text, width))
+                    .filter_map(|(point, color)| color.map(|color| Pixel(point, color))),
+            ),
+        }
+    }
+}
+
+impl<C: PixelColor> TextRenderer for CellTextStyle<'_, C> {
+    type Color = C;
+
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        // Only decorations are drawn without a text or background colour, across the spacing after
+        // the last character too, so leave those to MonoTextStyle
+        let transparent_glyphs =
+            self.style.text_color.is_none() && self.style.background_color.is_none();
+        if transparent_glyphs || !self.fits_in_cells() {
+            return self.style.draw_string(text, position, baseline, target);
+        }
+
+        let metrics = self.style.measure_string(text, position, baseline);
+        let top_left = Point::new(position.x, metrics.bounding_box.top_left.y);
+        let width = (metrics.next_position.x - position.x) as u32;
+
+        if self.whole_lines {
+            if width > 0 {
+                self.draw_span(text, top_left, width, target)?;
+            }
+        } else {
+            let font = self.style.font;
+            let advance = font.character_size.width + font.character_spacing;
+            let mut x = 0;
+            for (index, ch) in text.char_indices() {
+                // Each cell takes the spacing after it, except for the last one
+                let cell = &text[index..index + ch.len_utf8()];
+                let cell_width = advance.min(width - x);
+                self.draw_span(cell, top_left + Point::new(x as i32, 0), cell_width, target)?;
+                x += cell_width;
+            }
+        }
+
+        Ok(metrics.next_position)
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.style
+            .draw_whitespace(width, position, baseline, target)
+    }
+
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        self.style.measure_string(text, position, baseline)
+    }
+
+    fn line_height(&self) -> u32 {
+        self.style.line_height()
+    }
+}
+
+impl<C: PixelColor> CharacterStyle for CellTextStyle<'_, C> {
+    type Color = C;
+
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.style.set_text_color(text_color);
+    }
+
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.style.set_background_color(background_color);
+    }
+
+    fn set_underline_color(&mut self, underline_color: DecorationColor<Self::Color>) {
+        self.style.set_underline_color(underline_color);
+    }
+
+    fn set_strikethrough_color(&mut self, strikethrough_color: DecorationColor<Self::Color>) {
+        self.style.set_strikethrough_color(strikethrough_color);
+    }
+}
+
+impl<'a, C: PixelColor> From<MonoTextStyle<'a, C>> for CellTextStyle<'a, C> {
+    fn from(style: MonoTextStyle<'a, C>) -> Self {
+        CellTextStyle::new(style)
+    }
+}
+
+fn decoration_color<C: PixelColor>(
+    decoration: DecorationColor<C>,
+    text_color: Option<C>,
+) -> Option<C> {
+    match decoration {
+        DecorationColor::None => None,
+        DecorationColor::TextColor => text_color,
+        DecorationColor::Custom(color) => Some(color),
+    }
+}
+
+/// Returns row `y` of the glyph for `ch`, with bit `x` set for each set pixel.
+fn glyph_row(font: &MonoFont, ch: char, y: u32) -> u32 {
+    let cell = font.character_size;
+    let glyphs_per_row = font.image.size().width / cell.width;
+    let index = font.glyph_mapping.index(ch) as u32;
+    let origin = Point::new(
+        (index % glyphs_per_row * cell.width) as i32,
+        (index / glyphs_per_row * cell.height + y) as i32,
+    );
+
+    let mut row = RowCapture(0);
+    let sub_image = font
+        .image
+        .sub_image(&Rectangle::new(origin, Size::new(cell.width, 1)));
+    // Drawing to a RowCapture can't fail
+    let _ = Image::new(&sub_image, Point::zero()).draw(&mut row);
+    row.0
+}
+
+/// A one row draw target that records set pixels as bits.
+struct RowCapture(u32);
+
+impl DrawTarget for RowCapture {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if color.is_on() && point.y == 0 && (0..MAX_GLYPH_WIDTH as i32).contains(&point.x) {
+                self.0 |= 1 << point.x;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl OriginDimensions for RowCapture {
+    fn size(&self) -> Size {
+        Size::new(MAX_GLYPH_WIDTH, 1)
+    }
+}
@@ -0,0 +1,314 @@
+//! Drawing text straight into packed 1 bit per pixel framebuffers.
+//!
+//! Monochrome panels are often driven from a framebuffer kept in RAM, with 8 pixels per byte and
+//! the leftmost pixel in the most significant bit. [`Framebuffer`] copies the rows of each glyph
+//! from the font's sprite sheet into such a buffer with shifts and masks, a byte at a time, rather
+//! than going through a `DrawTarget` pixel by pixel.
+//!
+//! ```rust
+//! use embedded_graphics::prelude::*;
+//! use profont::framebuffer::{BlitMode, Framebuffer};
+//! use profont::PROFONT_9_POINT_SHEET;
+//!
+//! // A 128x64 panel
+//! let mut buffer = [0u8; 128 / 8 * 64];
+//! let mut framebuffer = Framebuffer::new(&mut buffer, 128, 64);
+//!
+//! let next = framebuffer.draw_text(
+//!     &PROFONT_9_POINT_SHEET,
+//!     "Hello world",
+//!     Point::new(3, 2),
+//!     BlitMode::Copy,
+//! );
+//! assert_eq!(next, Point::new(3 + 11 * 6, 2));
+//! ```
+
+use core::fmt;
+
+use embedded_graphics::{mono_font::MonoFont, prelude::*};
+
+/// The sprite sheet of a size, with the packed rows of pixels it's made from.
+///
+/// Each size has a sheet, such as [`PROFONT_7_POINT_SHEET`](crate::PROFONT_7_POINT_SHEET).
+#[derive(Clone, Copy)]
+pub struct GlyphSheet<'a> {
+    font: &'a MonoFont<'a>,
+    data: &'a [u8],
+}
+
+impl<'a> GlyphSheet<'a> {
+    /// `data` must be the data of `font`'s image, with glyphs no wider than 32 pixels including
+    /// the spacing.
+    pub(crate) const fn new(font: &'a MonoFont<'a>, data: &'a [u8]) -> Self {
+        GlyphSheet { font, data }
+    }
+
+    /// Returns the font the sheet belongs to.
+    pub fn font(&self) -> &'a MonoFont<'a> {
+        self.font
+    }
+
+    /// Returns the position of the top left corner of the glyph for `ch` in the sheet.
+    fn glyph_origin(&self, ch: char) -> (u32, u32) {
+        let cell = self.font.character_size;
+        let glyphs_per_row = self.font.image.size().width / cell.width;
+        let index = self.font.glyph_mapping.index(ch) as u32;
+        (
+            index % glyphs_per_row * cell.width,
+            index / glyphs_per_row * cell.height,
+        )
+    }
+
+    /// Returns the row of a glyph at `x`, `y` in the sheet, with the leftmost pixel in the most
+    /// significant bit.
+    fn glyph_row(&self, x: u32, y: u32) -> u32 {
+        let cell = self.font.character_size;
+
+        // Read the 5 bytes that can hold a glyph row of up to 32 pixels at any bit offset
+        let bytes_per_row = (self.font.image.size().width as usize + 7) / 8;
+        let row = &self.data[y as usize * bytes_per_row..][..bytes_per_row];
+        let first = x as usize / 8;
+        let bits = row[first..]
+            .iter()
+            .take(5)
+            .enumerate()
+            .fold(0u64, |bits, (i, &byte)| {
+                bits | u64::from(byte) << (56 - 8 * i)
+            });
+        let glyph = ((bits << (x % 8)) >> 32) as u32;
+        glyph & left_mask(cell.width)
+    }
+}
+
+impl fmt::Debug for GlyphSheet<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GlyphSheet")
+            .field("font", self.font)
+            .field("data", &format_args!("{} bytes", self.data.len()))
+            .finish()
+    }
+}
+
+/// How the pixels of each glyph are combined with the framebuffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BlitMode {
+    /// Turns on the set pixels of the glyph, leaving the rest of the cell untouched.
+    Set,
+    /// Turns off the set pixels of the glyph, leaving the rest of the cell untouched.
+    Clear,
+    /// Copies the whole cell, turning on the set pixels of the glyph and off the rest, including
+    /// the spacing between characters.
+    Copy,
+    /// Copies the whole cell inverted, turning off the set pixels of the glyph and on the rest,
+    /// including the spacing between characters.
+    CopyInverted,
+}
+
+/// A packed 1 bit per pixel framebuffer, borrowed from the caller.
+///
+/// Each row starts on a byte boundary, and the leftmost pixel of each byte is in its most
+/// significant bit. Anything drawn outside of the framebuffer is clipped.
+#[derive(Debug)]
+pub struct Framebuffer<'a> {
+    buffer: &'a mut [u8],
+    size: Size,
+    bytes_per_row: usize,
+}
+
+impl<'a> Framebuffer<'a> {
+    /// Wraps `buffer`, with rows of `width` pixels rounded up to whole bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is too small for `height` rows.
+    pub fn new(buffer: &'a mut [u8], width: u32, height: u32) -> Self {
+        let bytes_per_row = (width as usize + 7) / 8;
+        Self::with_bytes_per_row(buffer, width, height, bytes_per_row)
+    }
+
+    /// Wraps `buffer`, with rows of `bytes_per_row` bytes. This suits panels whose rows are
+    /// padded beyond the visible width.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_row` is too small for `width` pixels, or `buffer` is too small for
+    /// `height` rows.
+    pub fn with_bytes_per_row(
+        buffer: &'a mut [u8],
+        width: u32,
+        height: u32,
+        bytes_per_row: usize,
+    ) -> Self {
+        assert!(
+            bytes_per_row * 8 >= width as usize,
+            "rows of {} bytes can't hold {} pixels",
+            bytes_per_row,
+            width
+        );
+        assert!(
+            buffer.len() >= bytes_per_row * height as usize,
+            "a buffer of {} bytes can't hold {} rows of {} bytes",
+            buffer.len(),
+            height,
+            bytes_per_row
+        );
+        Framebuffer {
+            buffer,
+            size: Size::new(width, height),
+            bytes_per_row,
+        }
+    }
+
+    /// Returns the size of the framebuffer in pixels.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Returns the packed pixels.
+    pub fn buffer(&self) -> &[u8] {
+        self.buffer
+    }
+
+    /// Draws `text` with its top left corner at `position`, returning the position after the
+    /// last character.
+    ///
+    /// As with `Text`, lines are split on `\n` and placed a character height apart, and
+    /// characters outside of the font are drawn as `?`. The spacing between characters is
+    /// filled by the copy modes, but not the spacing after the last character of a line.
+    pub fn draw_text(
+        &mut self,
+        sheet: &GlyphSheet<'_>,
+        text: &str,
+        position: Point,
+        mode: BlitMode,
+    ) -> Point {
+        let font = sheet.font;
+        let advance = (font.character_size.width + font.character_spacing) as i32;
+        let mut next = position;
+
+        for (index, line) in text.split('\n').enumerate() {
+            // Remove the '\r' of '\r\n' line endings, like Text does
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let mut x = position.x;
+            let y = position.y + index as i32 * font.character_size.height as i32;
+            let mut chars = line.chars().peekable();
+            while let Some(ch) = chars.next() {
+                let spacing = if chars.peek().is_some() {
+                    font.character_spacing
+                } else {
+                    0
+                };
+                self.blit_glyph(sheet, ch, Point::new(x, y), spacing, mode);
+                x += advance;
+            }
+            if x > position.x {
+                x -= font.character_spacing as i32;
+            }
+            next = Point::new(x, y);
+        }
+
+        next
+    }
+
+    /// Draws the glyph for `ch` with its top left corner at `position`. The copy modes also fill
+    /// the spacing after the glyph.
+    pub fn draw_glyph(
+        &mut self,
+        sheet: &GlyphSheet<'_>,
+        ch: char,
+        position: Point,
+        mode: BlitMode,
+    ) {
+        self.blit_glyph(sheet, ch, position, sheet.font.character_spacing, mode);
+    }
+
+    /// Draws the glyph for `ch` followed by `spacing` unset columns.
+    fn blit_glyph(
+        &mut self,
+        sheet: &GlyphSheet<'_>,
+        ch: char,
+        position: Point,
+        spacing: u32,
+        mode: BlitMode,
+    ) {
+        let cell = sheet.font.character_size;
+        // Only the copy modes touch the pixels that aren't set in the glyph
+        let width = match mode {
+            BlitMode::Set | BlitMode::Clear => cell.width,
+            BlitMode::Copy | BlitMode::CopyInverted => cell.width + spacing,
+        };
+
+        // Clip the cell to the framebuffer
+        let left = position.x.max(0);
+        let right = (position.x + width as i32).min(self.size.width as i32);
+        let top = position.y.max(0);
+        let bottom = (position.y + cell.height as i32).min(self.size.height as i32);
+        if left >= right || top >= bottom {
+            return;
+        }
+        let first_byte = left as usize / 8;
+        let last_byte = (right - 1) as usize / 8;
+        let (glyph_x, glyph_y) = sheet.glyph_origin(ch);
+
+        // Line the cell up with each byte it covers, masking off the pixels outside of the cell and
+        // the framebuffer. A cell of up to 32 pixels covers at most 5 bytes.
+        let mut masks = [0u8; 5];
+        for (index, mask) in masks
+            .iter_mut()
+            .enumerate()
+            .take(last_byte - first_byte + 1)
+        {
+            let byte_x = (first_byte + index) as i32 * 8;
+            *mask = shifted_byte(left_mask(width), position.x - byte_x)
+                & pixels_from(left - byte_x)
+                & !pixels_from(right - byte_x);
+        }
+
+        for y in top..bottom {
+            let glyph = sheet.glyph_row(glyph_x, glyph_y + (y - position.y) as u32);
+            let row = &mut self.buffer[y as usize * self.bytes_per_row..][first_byte..=last_byte];
+            for (index, (byte, &mask)) in row.iter_mut().zip(masks.iter()).enumerate() {
+                let byte_x = (first_byte + index) as i32 * 8;
+                let pixels = shifted_byte(glyph, position.x - byte_x);
+
+                *byte = match mode {
+                    BlitMode::Set => *byte | (pixels & mask),
+                    BlitMode::Clear => *byte & !(pixels & mask),
+                    BlitMode::Copy => (*byte & !mask) | (pixels & mask),
+                    BlitMode::CopyInverted => (*byte & !mask) | (!pixels & mask),
+                };
+            }
+        }
+    }
+}
+
+/// Returns a row with the leftmost `width` pixels set.
+fn left_mask(width: u32) -> u32 {
+    if width >= 32 {
+        !0
+    } else {
+        !(!0 >> width)
+    }
+}
+
+/// Returns the 8 pixels of a byte, when `row` starts `offset` pixels into it.
+///
+/// Pixels before the start of `row` are unset, and `row` is left aligned.
+fn shifted_byte(row: u32, offset: i32) -> u8 {
+    // With the row below a byte of unset pixels, the byte starts 8 - offset pixels in
+    let shift = 8 - offset;
+    if (1..40).contains(&shift) {
+        ((u64::from(row) << 24 << shift) >> 56) as u8
+    } else {
+        0
+    }
+}
+
+/// Returns a byte with the pixels from `offset` onwards set.
+fn pixels_from(offset: i32) -> u8 {
+    match offset {
+        i32::MIN..=0 => 0xff,
+        1..=7 => 0xff >> offset,
+        _ => 0,
+    }
+}
@@ -34,7 +34,26 @@
 //! ### Glyph Coverage
 //!
 //! This crate provides support for [ISO/IEC 8859-1](https://en.wikipedia.org/wiki/ISO/IEC_8859-1)
-//! (latin1), although do note that the font is missing a few glyphs in this range.
+//! (latin1), although do note that the font is missing a few glyphs in this range. The characters
+//! are listed by [`GLYPH_MAPPING`].
+//!
+//! ### Framebuffers
+//!
+//! The [`framebuffer`] module draws text straight into packed 1 bit per pixel framebuffers, using
+//! the glyph sheet of each size such as [`PROFONT_7_POINT_SHEET`].
+//!
+//! ### Testing
+//!
+//! The `testing` feature adds the [`testing`] module, with a draw target and assertions for
+//! testing code that draws text with ProFont. It needs `alloc`.
+
+#[cfg(feature = "testing")]
+extern crate alloc;
+
+mod cell_text_style;
+pub mod framebuffer;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 use embedded_graphics::{
     geometry::Size,
@@ -42,21 +61,27 @@ use embedded_graphics::{
     mono_font::{mapping::StrGlyphMapping, DecorationDimensions, MonoFont},
 };
 
+pub use cell_text_style::CellTextStyle;
+pub use framebuffer::GlyphSheet;
+
 const CHARS_PER_ROW: u32 = 32;
 
 /// Character ranges for all fonts.
 ///
 /// This consists of two character ranges - ASCII from ' ' to '~', then ISO 8859-1 from `&nbsp;`
 /// (HTML notation) to `ÿ`. Unknown characters fall back to `?`.
-const GLYPH_MAPPING: StrGlyphMapping =
+///
+/// Every size uses this mapping, so it can be used to check which characters can be drawn, or to
+/// find a glyph's position in the sprite sheets: glyph `index` is in column `index % 32` and row
+/// `index / 32`. The characters are only ever added to, at the end of the mapping.
+pub const GLYPH_MAPPING: StrGlyphMapping =
     StrGlyphMapping::new("\0 ~\0\u{00A0}ÿ", '?' as usize - ' ' as usize);
 
+const PROFONT_7_POINT_DATA: &[u8] = include_bytes!("../data/ProFont7Point.raw");
+
 /// The 7 point size with a character size of 5x10 pixels.
 pub const PROFONT_7_POINT: MonoFont = MonoFont {
-    image: ImageRaw::new(
-        include_bytes!("../data/ProFont7Point.raw"),
-        CHARS_PER_ROW * 5,
-    ),
+    image: ImageRaw::new(PROFONT_7_POINT_DATA, CHARS_PER_ROW * 5),
 
     character_size: Size::new(5, 10),
     character_spacing: 0,
@@ -66,12 +91,15 @@ pub const PROFONT_7_POINT: MonoFont = MonoFont {
     glyph_mapping: &GLYPH_MAPPING,
 };
 
+/// The glyph sheet of the 7 point size, for drawing into packed framebuffers.
+pub const PROFONT_7_POINT_SHEET: GlyphSheet =
+    GlyphSheet::new(&PROFONT_7_POINT, PROFONT_7_POINT_DATA);
+
+const PROFONT_9_POINT_DATA: &[u8] = include_bytes!("../data/ProFont9Point.raw");
+
 /// The 9 point size with a character size of 6x11 pixels.
 pub const PROFONT_9_POINT: MonoFont = MonoFont {
-    image: ImageRaw::new(
-        include_bytes!("../data/ProFont9Point.raw"),
-        CHARS_PER_ROW * 6,
-    ),
+    image: ImageRaw::new(PROFONT_9_POINT_DATA, CHARS_PER_ROW * 6),
 
     character_size: Size::new(6, 11),
     character_spacing: 0,
@@ -81,12 +109,15 @@ pub const PROFONT_9_POINT: MonoFont = MonoFont {
     glyph_mapping: &GLYPH_MAPPING,
 };
 
+/// The glyph sheet of the 9 point size, for drawing into packed framebuffers.
+pub const PROFONT_9_POINT_SHEET: GlyphSheet =
+    GlyphSheet::new(&PROFONT_9_POINT, PROFONT_9_POINT_DATA);
+
+const PROFONT_10_POINT_DATA: &[u8] = include_bytes!("../data/ProFont10Point.raw");
+
 /// The 10 point size with a character size of 6x12 pixels.
 pub const PROFONT_10_POINT: MonoFont = MonoFont {
-    image: ImageRaw::new(
-        include_bytes!("../data/ProFont10Point.raw"),
-        CHARS_PER_ROW * 6,
-    ),
+    image: ImageRaw::new(PROFONT_10_POINT_DATA, CHARS_PER_ROW * 6),
 
     character_size: Size::new(6, 12),
     character_spacing: 1,
@@ -96,12 +127,15 @@ pub const PROFONT_10_POINT: MonoFont = MonoFont {
     glyph_mapping: &GLYPH_MAPPING,
 };
 
+/// The glyph sheet of the 10 point size, for drawing into packed framebuffers.
+pub const PROFONT_10_POINT_SHEET: GlyphSheet =
+    GlyphSheet::new(&PROFONT_10_POINT, PROFONT_10_POINT_DATA);
+
+const PROFONT_12_POINT_DATA: &[u8] = include_bytes!("../data/ProFont12Point.raw");
+
 /// The 12 point size with a character size of 7x15 pixels.
 pub const PROFONT_12_POINT: MonoFont = MonoFont {
-    image: ImageRaw::new(
-        include_bytes!("../data/ProFont12Point.raw"),
-        CHARS_PER_ROW * 7,
-    ),
+    image: ImageRaw::new(PROFONT_12_POINT_DATA, CHARS_PER_ROW * 7),
 
     character_size: Size::new(7, 15),
     character_spacing: 1,
@@ -111,12 +145,15 @@ pub const PROFONT_12_POINT: MonoFont = MonoFont {
     glyph_mapping: &GLYPH_MAPPING,
 };
 
+/// The glyph sheet of the 12 point size, for drawing into packed framebuffers.
+pub const PROFONT_12_POINT_SHEET: GlyphSheet =
+    GlyphSheet::new(&PROFONT_12_POINT, PROFONT_12_POINT_DATA);
+
+const PROFONT_14_POINT_DATA: &[u8] = include_bytes!("../data/ProFont14Point.raw");
+
 /// The 14 point size with a character size of 10x17 pixels.
 pub const PROFONT_14_POINT: MonoFont = MonoFont {
-    image: ImageRaw::new(
-        include_bytes!("../data/ProFont14Point.raw"),
-        CHARS_PER_ROW * 10,
-    ),
+    image: ImageRaw::new(PROFONT_14_POINT_DATA, CHARS_PER_ROW * 10),
 
     character_size: Size::new(10, 17),
     character_spacing: 0,
@@ -126,12 +163,15 @@ pub const PROFONT_14_POINT: MonoFont = MonoFont {
     glyph_mapping: &GLYPH_MAPPING,
 };
 
+/// The glyph sheet of the 14 point size, for drawing into packed framebuffers.
+pub const PROFONT_14_POINT_SHEET: GlyphSheet =
+    GlyphSheet::new(&PROFONT_14_POINT, PROFONT_14_POINT_DATA);
+
+const PROFONT_18_POINT_DATA: &[u8] = include_bytes!("../data/ProFont18Point.raw");
+
 /// The 18 point size with a character size of 12x22 pixels.
 pub const PROFONT_18_POINT: MonoFont = MonoFont {
-    image: ImageRaw::new(
-        include_bytes!("../data/ProFont18Point.raw"),
-        CHARS_PER_ROW * 12,
-    ),
+    image: ImageRaw::new(PROFONT_18_POINT_DATA, CHARS_PER_ROW * 12),
 
     character_size: Size::new(12, 22),
     character_spacing: 0,
@@ -141,12 +181,15 @@ pub const PROFONT_18_POINT: MonoFont = MonoFont {
     glyph_mapping: &GLYPH_MAPPING,
 };
 
+/// The glyph sheet of the 18 point size, for drawing into packed framebuffers.
+pub const PROFONT_18_POINT_SHEET: GlyphSheet =
+    GlyphSheet::new(&PROFONT_18_POINT, PROFONT_18_POINT_DATA);
+
+const PROFONT_24_POINT_DATA: &[u8] = include_bytes!("../data/ProFont24Point.raw");
+
 /// The 24 point size with a character size of 16x29 pixels.
 pub const PROFONT_24_POINT: MonoFont = MonoFont {
-    image: ImageRaw::new(
-        include_bytes!("../data/ProFont24Point.raw"),
-        CHARS_PER_ROW * 16,
-    ),
+    image: ImageRaw::new(PROFONT_24_POINT_DATA, CHARS_PER_ROW * 16),
 
     character_size: Size::new(16, 29),
     character_spacing: 0,
@@ -155,3 +198,7 @@ pub const PROFONT_24_POINT: MonoFont = MonoFont {
     strikethrough: DecorationDimensions::new(16, 2),
     glyph_mapping: &GLYPH_MAPPING,
 };
+
+/// The glyph sheet of the 24 point size, for drawing into packed framebuffers.
+pub const PROFONT_24_POINT_SHEET: GlyphSheet =
+    GlyphSheet::new(&PROFONT_24_POINT, PROFONT_24_POINT_DATA);
@@ -0,0 +1,368 @@
+//! Helpers for testing code that draws text with ProFont, enabled by the `testing` feature.
+//!
+//! Pixels are written as ASCII art using the same characters as the patterns of
+//! [`MockDisplay`](embedded_graphics::mock_display::MockDisplay): `#` and `.` for
+//! `BinaryColor::On` and `Off`, the letters listed in
+//! [`ColorMapping`] for RGB colours, and a space
+//! for pixels that weren't drawn. Unlike `MockDisplay` the display isn't limited to 64x64 pixels.
+//!
+//! ```rust
+//! use embedded_graphics::{
+//!     mono_font::MonoTextStyle,
+//!     pixelcolor::BinaryColor,
+//!     prelude::*,
+//!     text::{Baseline, Text},
+//! };
+//! use profont::{testing, PROFONT_7_POINT};
+//!
+//! # fn main() -> Result<(), core::convert::Infallible> {
+//! let style = MonoTextStyle::new(&PROFONT_7_POINT, BinaryColor::On);
+//! assert_eq!(
+//!     testing::render("Hi", style),
+//!     concat!(
+//!         "          \n",
+//!         "          \n",
+//!         "#  #   #  \n",
+//!         "#  #      \n",
+//!         "####  ##  \n",
+//!         "#  #   #  \n",
+//!         "#  #   #  \n",
+//!         "#  #  ### \n",
+//!         "          \n",
+//!         "          \n",
+//!     )
+//! );
+//!
+//! let mut display = testing::RecordingDisplay::new();
+//! Text::with_baseline("Hi", Point::new(3, 4), style, Baseline::Top).draw(&mut display)?;
+//! testing::assert_text(&display, "Hi", Point::new(3, 4), style);
+//! # Ok(()) }
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use core::convert::Infallible;
+use core::fmt::Write;
+
+use embedded_graphics::{
+    image::GetPixel,
+    mock_display::ColorMapping,
+    mono_font::MonoTextStyle,
+    prelude::*,
+    primitives::Rectangle,
+    text::{Baseline, Text},
+};
+
+/// A draw target without bounds that records the last colour drawn to each pixel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordingDisplay<C> {
+    /// Colours keyed by `(y, x)`, so that they are in row-major order.
+    pixels: BTreeMap<(i32, i32), C>,
+}
+
+impl<C: PixelColor> RecordingDisplay<C> {
+    /// Creates an empty display.
+    pub fn new() -> Self {
+        RecordingDisplay {
+            pixels: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the colour last drawn at `point`, or `None` if nothing was drawn there.
+    pub fn get_pixel(&self, point: Point) -> Option<C> {
+        self.pixels.get(&(point.y, point.x)).copied()
+    }
+
+    /// Returns the drawn pixels in row-major order.
+    pub fn pixels(&self) -> impl Iterator<Item = Pixel<C>> + '_ {
+        self.pixels
+            .iter()
+            .map(|(&(y, x), &color)| Pixel(Point::new(x, y), color))
+    }
+
+    /// Returns the smallest rectangle that contains all of the drawn pixels.
+    pub fn extent(&self) -> Rectangle {
+        let mut points = self.pixels.keys().map(|&(y, x)| Point::new(x, y));
+        let first = match points.next() {
+            Some(point) => point,
+            None => return Rectangle::zero(),
+        };
+        let (min, max) = points.fold((first, first), |(min, max), point| {
+            (min.component_min(point), max.component_max(point))
+        });
+        Rectangle::with_corners(min, max)
+    }
+}
+
+impl<C: PixelColor + ColorMapping> RecordingDisplay<C> {
+    /// Writes the pixels in `area` as ASCII art, with a line per row.
+    pub fn ascii_art(&self, area: &Rectangle) -> String {
+        let mut art = String::new();
+        for y in area.rows() {
+            for x in area.columns() {
+                art.push(to_char(self.get_pixel(Point::new(x, y))));
+            }
+            art.push('\n');
+        }
+        art
+    }
+
+    /// Writes all of the drawn pixels as ASCII art.
+    pub fn to_ascii_art(&self) -> String {
+        self.ascii_art(&self.extent())
+    }
+}
+
+impl<C: PixelColor> Default for RecordingDisplay<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: PixelColor> DrawTarget for RecordingDisplay<C> {
+    type Color = C;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.pixels.insert((point.y, point.x), color);
+        }
+        Ok(())
+    }
+}
+
+impl<C: PixelColor> OriginDimensions for RecordingDisplay<C> {
+    // There are no bounds, so report the largest size that still fits in a Rectangle
+    fn size(&self) -> Size {
+        Size::new_equal(i32::MAX as u32)
+    }
+}
+
+impl<C: PixelColor> GetPixel for RecordingDisplay<C> {
+    type Color = C;
+
+    fn pixel(&self, point: Point) -> Option<C> {
+        self.get_pixel(point)
+    }
+}
+
+/// The number of calls made to each drawing method of a [`CountingDisplay`], and the pixels
+/// passed to them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DrawCounts {
+    /// Calls to `draw_iter`.
+    pub draw_iter_calls: usize,
+    /// Pixels yielded by the iterators passed to `draw_iter`.
+    pub draw_iter_pixels: usize,
+    /// Calls to `fill_contiguous`.
+    pub fill_contiguous_calls: usize,
+    /// Pixels in the areas passed to `fill_contiguous`.
+    pub fill_contiguous_pixels: usize,
+    /// Calls to `fill_solid`.
+    pub fill_solid_calls: usize,
+    /// Pixels in the areas passed to `fill_solid`.
+    pub fill_solid_pixels: usize,
+}
+
+impl DrawCounts {
+    /// Returns the total number of calls.
+    pub fn calls(&self) -> usize {
+        self.draw_iter_calls + self.fill_contiguous_calls + self.fill_solid_calls
+    }
+
+    /// Returns the total number of pixels.
+    pub fn pixels(&self) -> usize {
+        self.draw_iter_pixels + self.fill_contiguous_pixels + self.fill_solid_pixels
+    }
+}
+
+/// A draw target that counts the calls made to it before passing them on to another target.
+///
+/// Only the calls made by the code drawing to the display are counted. The default
+/// implementations of `fill_contiguous` and `fill_solid` in the wrapped target, which call its
+/// `draw_iter`, are not, so the counts show what a display driver with its own implementations of
+/// each method would receive.
+///
+/// ```rust
+/// use embedded_graphics::{
+///     mono_font::MonoTextStyle,
+///     pixelcolor::BinaryColor,
+///     prelude::*,
+///     text::Text,
+/// };
+/// use profont::{testing::{CountingDisplay, RecordingDisplay}, PROFONT_9_POINT};
+///
+/// # fn main() -> Result<(), core::convert::Infallible> {
+/// let mut display = CountingDisplay::new(RecordingDisplay::new());
+/// let style = MonoTextStyle::new(&PROFONT_9_POINT, BinaryColor::On);
+/// Text::new("Hello", Point::new(0, 8), style).draw(&mut display)?;
+///
+/// // Without a background colour each glyph is drawn as an iterator of its set pixels
+/// assert_eq!(display.counts().draw_iter_calls, 5);
+/// assert_eq!(display.counts().fill_contiguous_calls, 0);
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CountingDisplay<D> {
+    display: D,
+    counts: DrawCounts,
+}
+
+impl<D: DrawTarget> CountingDisplay<D> {
+    /// Wraps `display`, with all counts at zero.
+    pub fn new(display: D) -> Self {
+        CountingDisplay {
+            display,
+            counts: DrawCounts::default(),
+        }
+    }
+
+    /// Returns the counts since the display was created or last reset.
+    pub fn counts(&self) -> DrawCounts {
+        self.counts
+    }
+
+    /// Sets all counts to zero.
+    pub fn reset(&mut self) {
+        self.counts = DrawCounts::default();
+    }
+
+    /// Returns the wrapped display.
+    pub fn inner(&self) -> &D {
+        &self.display
+    }
+
+    /// Returns the wrapped display, consuming the counting display.
+    pub fn into_inner(self) -> D {
+        self.display
+    }
+}
+
+impl<D: DrawTarget> DrawTarget for CountingDisplay<D> {
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.counts.draw_iter_calls += 1;
+        let count = &mut self.counts.draw_iter_pixels;
+        self.display
+            .draw_iter(pixels.into_iter().inspect(|_| *count += 1))
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.counts.fill_contiguous_calls += 1;
+        self.counts.fill_contiguous_pixels += area_pixels(area);
+        self.display.fill_contiguous(area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.counts.fill_solid_calls += 1;
+        self.counts.fill_solid_pixels += area_pixels(area);
+        self.display.fill_solid(area, color)
+    }
+}
+
+impl<D: DrawTarget> Dimensions for CountingDisplay<D> {
+    fn bounding_box(&self) -> Rectangle {
+        self.display.bounding_box()
+    }
+}
+
+fn area_pixels(area: &Rectangle) -> usize {
+    area.size.width as usize * area.size.height as usize
+}
+
+/// Draws `text` with its top left corner at the origin and returns it as ASCII art.
+///
+/// Every pixel of the text's character cells is included, so the result has a line for each
+/// pixel row of the text.
+pub fn render<C>(text: &str, style: MonoTextStyle<'_, C>) -> String
+where
+    C: PixelColor + ColorMapping,
+{
+    let text = Text::with_baseline(text, Point::zero(), style, Baseline::Top);
+    let mut display = RecordingDisplay::new();
+    text.draw(&mut display).unwrap();
+    display.ascii_art(&text.bounding_box())
+}
+
+/// Asserts that `display` shows `text` drawn with `style`, with its top left corner at
+/// `position`.
+///
+/// Every pixel the text draws must have the same colour on the display. Pixels the text leaves
+/// untouched, which are the unset pixels of each glyph when the style has no background colour,
+/// only have to differ from the text colour. On failure the expected and actual pixels are shown
+/// side by side, followed by the pixels that differ.
+#[track_caller]
+pub fn assert_text<D, C>(display: &D, text: &str, position: Point, style: MonoTextStyle<'_, C>)
+where
+    D: GetPixel<Color = C>,
+    C: PixelColor + ColorMapping,
+{
+    let drawn = Text::with_baseline(text, position, style, Baseline::Top);
+    let mut expected = RecordingDisplay::new();
+    drawn.draw(&mut expected).unwrap();
+    let area = drawn.bounding_box();
+
+    let matches = |point: Point| {
+        let actual = display.pixel(point);
+        match expected.get_pixel(point) {
+            Some(color) => actual == Some(color),
+            None => style.text_color.is_none() || actual != style.text_color,
+        }
+    };
+    if area.points().all(matches) {
+        return;
+    }
+
+    // Wide enough for the headings of narrow text
+    let width = (area.size.width as usize).max("expected".len());
+    let mut message = String::new();
+    let _ = writeln!(
+        message,
+        "text {:?} at ({}, {}) doesn't match the display",
+        text, position.x, position.y
+    );
+    let _ = writeln!(
+        message,
+        "{:<width$}  {:<width$}  differences",
+        "expected",
+        "actual",
+        width = width
+    );
+    for y in area.rows() {
+        let row = || area.columns().map(move |x| Point::new(x, y));
+        let expected_row = row()
+            .map(|point| to_char(expected.get_pixel(point)))
+            .collect::<String>();
+        let actual_row = row()
+            .map(|point| to_char(display.pixel(point)))
+            .collect::<String>();
+        let differences = row()
+            .map(|point| if matches(point) { ' ' } else { '^' })
+            .collect::<String>();
+        let line = format!(
+            "{:<width$}  {:<width$}  {}",
+            expected_row,
+            actual_row,
+            differences,
+            width = width
+        );
+        let _ = writeln!(message, "{}", line.trim_end());
+    }
+    panic!("{}", message);
+}
+
+fn to_char<C: ColorMapping>(color: Option<C>) -> char {
+    color.map_or(' ', C::color_to_char)
+}
@@ -0,0 +1,239 @@
+//! Tests that `CellTextStyle` draws the same pixels as `MonoTextStyle`, with fewer calls.
+
+use embedded_graphics::{
+    mono_font::{MonoFont, MonoTextStyle, MonoTextStyleBuilder},
+    pixelcolor::Rgb888,
+    prelude::*,
+    text::{renderer::TextRenderer, Baseline, Text},
+};
+use profont::testing::{CountingDisplay, DrawCounts, RecordingDisplay};
+use profont::*;
+use proptest::prelude::*;
+
+mod common;
+use common::{all_chars, size, text_width, BACKGROUND_COLOR, SIZES, TEXT_COLOR};
+
+const BASELINES: [Baseline; 4] = [
+    Baseline::Top,
+    Baseline::Bottom,
+    Baseline::Middle,
+    Baseline::Alphabetic,
+];
+const UNDERLINE_COLOR: Rgb888 = Rgb888::RED;
+const STRIKETHROUGH_COLOR: Rgb888 = Rgb888::GREEN;
+
+/// Every combination of text colour, background colour and decorations.
+fn styles(font: &'static MonoFont<'static>) -> Vec<MonoTextStyle<'static, Rgb888>> {
+    let mut styles = Vec::new();
+    for &text_color in [None, Some(TEXT_COLOR)].iter() {
+        for &background_color in [None, Some(BACKGROUND_COLOR)].iter() {
+            for underline in 0..3 {
+                for strikethrough in 0..3 {
+                    let mut builder = MonoTextStyleBuilder::new().font(font);
+                    if let Some(color) = text_color {
+                        builder = builder.text_color(color);
+                    }
+                    if let Some(color) = background_color {
+                        builder = builder.background_color(color);
+                    }
+                    builder = match underline {
+                        1 => builder.underline(),
+                        2 => builder.underline_with_color(UNDERLINE_COLOR),
+                        _ => builder,
+                    };
+                    builder = match strikethrough {
+                        1 => builder.strikethrough(),
+                        2 => builder.strikethrough_with_color(STRIKETHROUGH_COLOR),
+                        _ => builder,
+                    };
+                    styles.push(builder.build());
+                }
+            }
+        }
+    }
+    styles
+}
+
+/// Draws `text` and returns the pixels, the counts and the next position.
+fn draw<S>(
+    text: &str,
+    position: Point,
+    style: S,
+    baseline: Baseline,
+) -> (RecordingDisplay<Rgb888>, DrawCounts, Point)
+where
+    S: TextRenderer<Color = Rgb888>,
+{
+    let mut display = CountingDisplay::new(RecordingDisplay::new());
+    let next = Text::with_baseline(text, position, style, baseline)
+        .draw(&mut display)
+        .unwrap();
+    let counts = display.counts();
+    (display.into_inner(), counts, next)
+}
+
+/// Asserts that both ways of drawing with `CellTextStyle` match `MonoTextStyle`.
+fn assert_same_pixels(
+    text: &str,
+    position: Point,
+    style: MonoTextStyle<'static, Rgb888>,
+    baseline: Baseline,
+) {
+    let (expected, _, expected_next) = draw(text, position, style, baseline);
+    for cell_style in [CellTextStyle::new(style), CellTextStyle::whole_lines(style)].iter() {
+        let (actual, _, next) = draw(text, position, *cell_style, baseline);
+        assert!(
+            actual == expected,
+            "{:?} drew different pixels to MonoTextStyle with {:?}",
+            cell_style,
+            baseline
+        );
+        assert_eq!(next, expected_next);
+    }
+}
+
+#[test]
+fn draws_every_char_the_same_as_mono_text_style() {
+    let text = all_chars();
+    for &font in SIZES.iter() {
+        let style = MonoTextStyleBuilder::new()
+            .font(font)
+            .text_color(TEXT_COLOR)
+            .background_color(BACKGROUND_COLOR)
+            .underline_with_color(UNDERLINE_COLOR)
+            .strikethrough()
+            .build();
+        assert_same_pixels(&text, Point::new(3, 40), style, Baseline::Top);
+    }
+}
+
+#[test]
+fn draws_every_style_the_same_as_mono_text_style() {
+    for &font in SIZES.iter() {
+        for style in styles(font) {
+            assert_same_pixels(
+                "Hello, world!\n½ 0O 1lI",
+                Point::new(3, 40),
+                style,
+                Baseline::Top,
+            );
+        }
+    }
+}
+
+#[test]
+fn draws_the_same_pixels_for_every_baseline() {
+    let style = MonoTextStyleBuilder::new()
+        .font(&PROFONT_12_POINT)
+        .text_color(TEXT_COLOR)
+        .background_color(BACKGROUND_COLOR)
+        .underline()
+        .build();
+    for &baseline in BASELINES.iter() {
+        assert_same_pixels("Hello\nworld", Point::new(-2, 5), style, baseline);
+    }
+}
+
+#[test]
+fn each_cell_is_one_fill() {
+    let text = "Hello, world!\nÀ bientôt";
+    let chars = text.chars().filter(|&ch| ch != '\n').count();
+    for &font in SIZES.iter() {
+        let style = MonoTextStyleBuilder::new()
+            .font(font)
+            .text_color(TEXT_COLOR)
+            .background_color(BACKGROUND_COLOR)
+            .underline_with_color(UNDERLINE_COLOR)
+            .strikethrough_with_color(STRIKETHROUGH_COLOR)
+            .build();
+        let (_, counts, _) = draw(
+            text,
+            Point::zero(),
+            CellTextStyle::new(style),
+            Baseline::Top,
+        );
+
+        // The cells cover the lines exactly, including the spacing and decorations
+        let lines_width = text_width(font, "Hello, world!") + text_width(font, "À bientôt");
+        assert_eq!(
+            counts,
+            DrawCounts {
+                fill_contiguous_calls: chars,
+                fill_contiguous_pixels: lines_width * font.character_size.height as usize,
+                ..DrawCounts::default()
+            },
+            "{:?}",
+            font.character_size
+        );
+    }
+}
+
+#[test]
+fn whole_lines_are_one_fill_each() {
+    for &font in SIZES.iter() {
+        let style = MonoTextStyleBuilder::new()
+            .font(font)
+            .text_color(TEXT_COLOR)
+            .background_color(BACKGROUND_COLOR)
+            .underline()
+            .build();
+        let (_, counts, _) = draw(
+            "Hello,\nworld!\n\nbye",
+            Point::zero(),
+            CellTextStyle::whole_lines(style),
+            Baseline::Top,
+        );
+
+        assert_eq!(counts.fill_contiguous_calls, 3);
+        assert_eq!(
+            counts.fill_contiguous_pixels,
+            (2 * text_width(font, "Hello,") + text_width(font, "bye"))
+                * font.character_size.height as usize
+        );
+        assert_eq!(counts.calls(), 3);
+    }
+}
+
+#[test]
+fn transparent_backgrounds_draw_each_cell_with_one_iterator() {
+    for &font in SIZES.iter() {
+        let style = MonoTextStyleBuilder::new()
+            .font(font)
+            .text_color(TEXT_COLOR)
+            .underline()
+            .strikethrough()
+            .build();
+        let (_, counts, _) = draw(
+            "Hello",
+            Point::zero(),
+            CellTextStyle::new(style),
+            Baseline::Top,
+        );
+        assert_eq!(counts.draw_iter_calls, 5);
+        assert_eq!(counts.calls(), 5);
+
+        let (_, counts, _) = draw(
+            "Hello",
+            Point::zero(),
+            CellTextStyle::whole_lines(style),
+            Baseline::Top,
+        );
+        assert_eq!(counts.calls(), 1);
+    }
+}
+
+proptest! {
+    #[test]
+    fn any_text_draws_the_same_pixels(
+        font in size(),
+        style in any::<prop::sample::Index>(),
+        text in "[ -~\u{a0}-\u{ff}\u{100}-\u{17f}\n]{0,30}",
+        x in -50..50,
+        y in -50..50,
+        baseline in prop::sample::select(&BASELINES[..]),
+    ) {
+        let styles = styles(font);
+        let style = styles[style.index(styles.len())];
+        assert_same_pixels(&text, Point::new(x, y), style, baseline);
+    }
+}
@@ -0,0 +1,49 @@
+//! Fixtures shared by the integration tests.
+
+// Each test crate only uses some of the fixtures
+#![allow(dead_code)]
+
+use embedded_graphics::{mono_font::MonoFont, pixelcolor::Rgb888, prelude::*};
+use profont::*;
+use proptest::prelude::*;
+
+pub const SIZES: [&MonoFont; 7] = [
+    &PROFONT_7_POINT,
+    &PROFONT_9_POINT,
+    &PROFONT_10_POINT,
+    &PROFONT_12_POINT,
+    &PROFONT_14_POINT,
+    &PROFONT_18_POINT,
+    &PROFONT_24_POINT,
+];
+pub const TEXT_COLOR: Rgb888 = Rgb888::WHITE;
+pub const BACKGROUND_COLOR: Rgb888 = Rgb888::BLUE;
+/// Characters per line of `mapped_chars`, the same as the sprite sheets.
+pub const CHARS_PER_LINE: usize = 32;
+
+/// Returns the width of a line of `text`.
+pub fn text_width(font: &MonoFont, text: &str) -> usize {
+    let chars = text.chars().count() as u32;
+    (chars * (font.character_size.width + font.character_spacing) - font.character_spacing) as usize
+}
+
+/// Every mapped character, on lines of `CHARS_PER_LINE`.
+pub fn mapped_chars() -> String {
+    let chars = GLYPH_MAPPING.chars().collect::<Vec<_>>();
+    chars
+        .chunks(CHARS_PER_LINE)
+        .map(|line| line.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Every mapped character, followed by a line of some that aren't mapped and a `\r\n` line ending.
+pub fn all_chars() -> String {
+    let mut text = mapped_chars();
+    text.push_str("\n\u{2603}\u{1F600}\t\r\nĀ");
+    text
+}
+
+pub fn size() -> impl Strategy<Value = &'static MonoFont<'static>> {
+    prop::sample::select(&SIZES[..])
+}
@@ -0,0 +1,179 @@
+//! Tests of the draw calls each size makes, so that regressions in drawing efficiency are caught.
+//!
+//! On displays driven over SPI or I2C every call usually sets up a new window on the panel, so the
+//! number of calls matters as much as the number of pixels.
+
+use embedded_graphics::{
+    mono_font::{MonoFont, MonoTextStyle, MonoTextStyleBuilder},
+    pixelcolor::Rgb888,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use profont::testing::{CountingDisplay, DrawCounts, RecordingDisplay};
+use profont::*;
+
+mod common;
+use common::{text_width, BACKGROUND_COLOR, SIZES, TEXT_COLOR};
+
+const TEXT: &str = "Hello, world! À bientôt";
+const DECORATION_COLOR: Rgb888 = Rgb888::RED;
+
+/// Draws `text` and returns the counts, along with the drawn pixels.
+fn draw(text: &str, style: MonoTextStyle<Rgb888>) -> (DrawCounts, RecordingDisplay<Rgb888>) {
+    let mut display = CountingDisplay::new(RecordingDisplay::new());
+    Text::with_baseline(text, Point::zero(), style, Baseline::Top)
+        .draw(&mut display)
+        .unwrap();
+    (display.counts(), display.into_inner())
+}
+
+/// Returns the number of set pixels in the glyphs of `text`.
+fn glyph_pixels(font: &MonoFont, text: &str) -> usize {
+    draw(text, MonoTextStyle::new(font, TEXT_COLOR))
+        .1
+        .pixels()
+        .count()
+}
+
+fn builder(font: &'static MonoFont<'static>) -> MonoTextStyleBuilder<'static, Rgb888> {
+    MonoTextStyleBuilder::new()
+        .font(font)
+        .text_color(TEXT_COLOR)
+}
+
+#[test]
+fn text_draws_an_iterator_of_set_pixels_per_glyph() {
+    for font in SIZES.iter() {
+        let (counts, _) = draw(TEXT, MonoTextStyle::new(font, TEXT_COLOR));
+        assert_eq!(
+            counts,
+            DrawCounts {
+                draw_iter_calls: TEXT.chars().count(),
+                draw_iter_pixels: glyph_pixels(font, TEXT),
+                ..DrawCounts::default()
+            },
+            "{:?}",
+            font.character_size
+        );
+    }
+}
+
+#[test]
+fn background_fills_each_cell_and_the_spacing_between_them() {
+    for font in SIZES.iter() {
+        let style = builder(font).background_color(BACKGROUND_COLOR).build();
+        let (counts, _) = draw(TEXT, style);
+
+        let chars = TEXT.chars().count();
+        let cell = font.character_size;
+        let spacing_fills = if font.character_spacing > 0 {
+            chars - 1
+        } else {
+            0
+        };
+        assert_eq!(
+            counts,
+            DrawCounts {
+                fill_contiguous_calls: chars,
+                fill_contiguous_pixels: chars * (cell.width * cell.height) as usize,
+                fill_solid_calls: spacing_fills,
+                fill_solid_pixels: spacing_fills * (font.character_spacing * cell.height) as usize,
+                ..DrawCounts::default()
+            },
+            "{:?}",
+            font.character_size
+        );
+    }
+}
+
+#[test]
+fn decorations_are_one_fill_each_per_line() {
+    for font in SIZES.iter() {
+        let plain = draw(TEXT, MonoTextStyle::new(font, TEXT_COLOR)).0;
+        let width = text_width(font, TEXT);
+
+        let underline = builder(font).underline_with_color(DECORATION_COLOR).build();
+        assert_eq!(
+            draw(TEXT, underline).0,
+            DrawCounts {
+                fill_solid_calls: 1,
+                fill_solid_pixels: width * font.underline.height as usize,
+                ..plain
+            },
+            "{:?}",
+            font.character_size
+        );
+
+        let strikethrough = builder(font)
+            .strikethrough_with_color(DECORATION_COLOR)
+            .build();
+        assert_eq!(
+            draw(TEXT, strikethrough).0,
+            DrawCounts {
+                fill_solid_calls: 1,
+                fill_solid_pixels: width * font.strikethrough.height as usize,
+                ..plain
+            },
+            "{:?}",
+            font.character_size
+        );
+    }
+}
+
+#[test]
+fn every_style_combined_adds_up() {
+    for font in SIZES.iter() {
+        let background = builder(font).background_color(BACKGROUND_COLOR);
+        let only_background = draw(TEXT, background.build()).0;
+        let all = background
+            .underline_with_color(DECORATION_COLOR)
+            .strikethrough_with_color(DECORATION_COLOR)
+            .build();
+        let width = text_width(font, TEXT);
+        let decoration_height = font.underline.height + font.strikethrough.height;
+
+        assert_eq!(
+            draw(TEXT, all).0,
+            DrawCounts {
+                fill_solid_calls: only_background.fill_solid_calls + 2,
+                fill_solid_pixels: only_background.fill_solid_pixels
+                    + width * decoration_height as usize,
+                ..only_background
+            },
+            "{:?}",
+            font.character_size
+        );
+    }
+}
+
+#[test]
+fn each_line_is_counted() {
+    let font = &PROFONT_12_POINT;
+    let style = builder(font).underline_with_color(DECORATION_COLOR).build();
+    let (counts, _) = draw("ab\nc", style);
+
+    assert_eq!(counts.draw_iter_calls, 3);
+    assert_eq!(counts.fill_solid_calls, 2);
+    assert_eq!(counts.calls(), 5);
+    assert_eq!(
+        counts.pixels(),
+        glyph_pixels(font, "ab\nc")
+            + (text_width(font, "ab") + text_width(font, "c")) * font.underline.height as usize
+    );
+}
+
+#[test]
+fn draws_are_passed_on_and_reset_clears_the_counts() {
+    let style = builder(&PROFONT_7_POINT)
+        .background_color(BACKGROUND_COLOR)
+        .build();
+    let mut display = CountingDisplay::new(RecordingDisplay::new());
+    Text::with_baseline("Hi", Point::zero(), style, Baseline::Top)
+        .draw(&mut display)
+        .unwrap();
+
+    assert_eq!(display.inner().pixels().count(), 2 * 5 * 10);
+    assert_eq!(display.counts().calls(), 2);
+    display.reset();
+    assert_eq!(display.counts(), DrawCounts::default());
+}
@@ -0,0 +1,243 @@
+//! Tests that blitting into a packed framebuffer matches drawing with `MonoTextStyle`.
+
+use std::convert::Infallible;
+
+use embedded_graphics::{
+    mono_font::{MonoTextStyle, MonoTextStyleBuilder},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use profont::framebuffer::{BlitMode, Framebuffer};
+use profont::*;
+use proptest::prelude::*;
+
+mod common;
+use common::all_chars;
+
+const SHEETS: [GlyphSheet; 7] = [
+    PROFONT_7_POINT_SHEET,
+    PROFONT_9_POINT_SHEET,
+    PROFONT_10_POINT_SHEET,
+    PROFONT_12_POINT_SHEET,
+    PROFONT_14_POINT_SHEET,
+    PROFONT_18_POINT_SHEET,
+    PROFONT_24_POINT_SHEET,
+];
+const MODES: [BlitMode; 4] = [
+    BlitMode::Set,
+    BlitMode::Clear,
+    BlitMode::Copy,
+    BlitMode::CopyInverted,
+];
+
+/// A packed framebuffer drawn to pixel by pixel, in the same layout as `Framebuffer`.
+struct Reference {
+    size: Size,
+    bytes_per_row: usize,
+    buffer: Vec<u8>,
+}
+
+impl DrawTarget for Reference {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if point.x < 0
+                || point.y < 0
+                || point.x >= self.size.width as i32
+                || point.y >= self.size.height as i32
+            {
+                continue;
+            }
+            let index = point.y as usize * self.bytes_per_row + point.x as usize / 8;
+            let mask = 0x80 >> (point.x % 8);
+            if color.is_on() {
+                self.buffer[index] |= mask;
+            } else {
+                self.buffer[index] &= !mask;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Reference {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+/// The `MonoTextStyle` that draws the same pixels as `mode`.
+fn style(sheet: &GlyphSheet<'static>, mode: BlitMode) -> MonoTextStyle<'static, BinaryColor> {
+    let builder = MonoTextStyleBuilder::new().font(sheet.font());
+    match mode {
+        BlitMode::Set => builder.text_color(BinaryColor::On),
+        BlitMode::Clear => builder.text_color(BinaryColor::Off),
+        BlitMode::Copy => builder
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off),
+        BlitMode::CopyInverted => builder
+            .text_color(BinaryColor::Off)
+            .background_color(BinaryColor::On),
+    }
+    .build()
+}
+
+/// A buffer of stripes, so that pixels left untouched are checked too.
+fn initial_buffer(bytes_per_row: usize, height: u32) -> Vec<u8> {
+    (0..bytes_per_row * height as usize)
+        .map(|i| if i % 3 == 0 { 0x5a } else { 0xc3 })
+        .collect()
+}
+
+/// Asserts that blitting `text` leaves the same buffer as drawing it with `MonoTextStyle`.
+fn assert_same_buffer(
+    sheet: &GlyphSheet<'static>,
+    text: &str,
+    position: Point,
+    mode: BlitMode,
+    size: Size,
+    bytes_per_row: usize,
+) {
+    let mut reference = Reference {
+        size,
+        bytes_per_row,
+        buffer: initial_buffer(bytes_per_row, size.height),
+    };
+    let expected_next = Text::with_baseline(text, position, style(sheet, mode), Baseline::Top)
+        .draw(&mut reference)
+        .unwrap();
+
+    let mut buffer = initial_buffer(bytes_per_row, size.height);
+    let mut framebuffer =
+        Framebuffer::with_bytes_per_row(&mut buffer, size.width, size.height, bytes_per_row);
+    let next = framebuffer.draw_text(sheet, text, position, mode);
+
+    assert_eq!(next, expected_next);
+    if buffer != reference.buffer {
+        let row = buffer
+            .chunks(bytes_per_row)
+            .zip(reference.buffer.chunks(bytes_per_row))
+            .position(|(actual, expected)| actual != expected)
+            .unwrap();
+        panic!(
+            "{:?} of {:?} at {:?} differs on row {}\n  actual: {:02x?}\nexpected: {:02x?}",
+            mode,
+            text,
+            position,
+            row,
+            &buffer[row * bytes_per_row..][..bytes_per_row],
+            &reference.buffer[row * bytes_per_row..][..bytes_per_row],
+        );
+    }
+}
+
+#[test]
+fn every_char_matches_mono_text_style_at_every_alignment() {
+    let text = all_chars();
+    for sheet in SHEETS.iter() {
+        let cell = sheet.font().character_size;
+        let size = Size::new(32 * (cell.width + 1) + 8, 8 * cell.height);
+        // Pad the rows, to check that the padding is left alone
+        let bytes_per_row = (size.width as usize + 7) / 8 + 1;
+        for &mode in MODES.iter() {
+            for x in 0..8 {
+                assert_same_buffer(sheet, &text, Point::new(x, 3), mode, size, bytes_per_row);
+            }
+        }
+    }
+}
+
+#[test]
+fn clips_to_every_edge() {
+    let size = Size::new(37, 21);
+    for sheet in SHEETS.iter() {
+        let cell = sheet.font().character_size;
+        for &mode in MODES.iter() {
+            for &(x, y) in [
+                (-3, 0),
+                (-(cell.width as i32) - 1, 0),
+                (-100, 0),
+                (30, 4),
+                (37, 4),
+                (4, -5),
+                (4, 15),
+                (-9, -9),
+                (33, 17),
+            ]
+            .iter()
+            {
+                assert_same_buffer(sheet, "Clip ½\nme", Point::new(x, y), mode, size, 5);
+            }
+        }
+    }
+}
+
+#[test]
+fn draw_glyph_fills_the_spacing_in_copy_modes() {
+    let mut buffer = [0u8; 2 * 12];
+    let mut framebuffer = Framebuffer::new(&mut buffer, 16, 12);
+    framebuffer.draw_glyph(
+        &PROFONT_10_POINT_SHEET,
+        ' ',
+        Point::new(1, 0),
+        BlitMode::CopyInverted,
+    );
+
+    // A space is a blank 6x12 cell, with 1 column of spacing
+    assert!(buffer.iter().step_by(2).all(|&byte| byte == 0b0111_1111));
+    assert!(buffer.iter().skip(1).step_by(2).all(|&byte| byte == 0));
+}
+
+#[test]
+fn empty_text_draws_nothing() {
+    let mut buffer = [0xa5u8; 8];
+    let next = Framebuffer::new(&mut buffer, 8, 8).draw_text(
+        &PROFONT_7_POINT_SHEET,
+        "",
+        Point::new(2, 3),
+        BlitMode::Copy,
+    );
+    assert_eq!(next, Point::new(2, 3));
+    assert_eq!(buffer, [0xa5; 8]);
+}
+
+#[test]
+#[should_panic(expected = "a buffer of 15 bytes can't hold 8 rows of 2 bytes")]
+fn buffer_must_hold_every_row() {
+    let mut buffer = [0u8; 15];
+    Framebuffer::new(&mut buffer, 9, 8);
+}
+
+#[test]
+#[should_panic(expected = "rows of 1 bytes can't hold 9 pixels")]
+fn rows_must_hold_the_width() {
+    let mut buffer = [0u8; 16];
+    Framebuffer::with_bytes_per_row(&mut buffer, 9, 8, 1);
+}
+
+fn sheet() -> impl Strategy<Value = GlyphSheet<'static>> {
+    prop::sample::select(&SHEETS[..])
+}
+
+proptest! {
+    #[test]
+    fn any_text_matches_mono_text_style(
+        sheet in sheet(),
+        mode in prop::sample::select(&MODES[..]),
+        text in "[ -~\u{a0}-\u{ff}\u{100}-\u{17f}\n]{0,20}",
+        x in -40..100,
+        y in -40..80,
+        width in 1u32..90,
+        height in 1u32..60,
+        padding in 0usize..3,
+    ) {
+        let bytes_per_row = (width as usize + 7) / 8 + padding;
+        assert_same_buffer(&sheet, &text, Point::new(x, y), mode, Size::new(width, height), bytes_per_row);
+    }
+}
@@ -0,0 +1,160 @@
+//! Property tests of how every size draws text.
+
+use embedded_graphics::{
+    mono_font::{MonoTextStyle, MonoTextStyleBuilder},
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::Rectangle,
+    text::{Baseline, Text},
+};
+use profont::testing::RecordingDisplay;
+use profont::*;
+use proptest::prelude::*;
+
+mod common;
+use common::{size, text_width, BACKGROUND_COLOR, TEXT_COLOR};
+
+const DECORATION_COLOR: Rgb888 = Rgb888::RED;
+
+/// Draws `text` with its top left corner at `position`.
+fn draw(text: &str, position: Point, style: MonoTextStyle<Rgb888>) -> RecordingDisplay<Rgb888> {
+    let mut display = RecordingDisplay::new();
+    Text::with_baseline(text, position, style, Baseline::Top)
+        .draw(&mut display)
+        .unwrap();
+    display
+}
+
+/// Returns the points drawn with `color`, in row-major order.
+fn points(display: &RecordingDisplay<Rgb888>, color: Rgb888) -> Vec<Point> {
+    display
+        .pixels()
+        .filter(|Pixel(_, c)| *c == color)
+        .map(|Pixel(point, _)| point)
+        .collect()
+}
+
+fn position() -> impl Strategy<Value = Point> {
+    (-50..50, -50..50).prop_map(|(x, y)| Point::new(x, y))
+}
+
+/// Any character that isn't in the font. Line endings are excluded, as `Text` splits lines on `\n`
+/// and drops the `\r` of `\r\n`.
+fn unmapped_char() -> impl Strategy<Value = char> {
+    any::<char>().prop_filter("mapped or line ending", |&ch| {
+        !GLYPH_MAPPING.contains(ch) && ch != '\n' && ch != '\r'
+    })
+}
+
+/// A line of mapped and unmapped characters.
+fn line() -> impl Strategy<Value = String> {
+    prop::collection::vec(
+        prop_oneof![
+            prop::sample::select(GLYPH_MAPPING.chars().collect::<Vec<_>>()),
+            unmapped_char(),
+        ],
+        1..40,
+    )
+    .prop_map(|chars| chars.into_iter().collect())
+}
+
+proptest! {
+    #[test]
+    fn every_glyph_draws_inside_its_cell(font in size(), position in position()) {
+        let cell = Rectangle::new(position, font.character_size);
+        let style = MonoTextStyle::new(font, TEXT_COLOR);
+        for ch in GLYPH_MAPPING.chars() {
+            let display = draw(&ch.to_string(), position, style);
+            for point in points(&display, TEXT_COLOR) {
+                prop_assert!(
+                    cell.contains(point),
+                    "U+{:04X} drew {:?} outside of {:?}",
+                    ch as u32,
+                    point,
+                    cell
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn background_fills_exactly_the_cell(font in size(), position in position()) {
+        let style = MonoTextStyleBuilder::new()
+            .font(font)
+            .text_color(TEXT_COLOR)
+            .background_color(BACKGROUND_COLOR)
+            .build();
+        let cell = Rectangle::new(position, font.character_size);
+        for ch in GLYPH_MAPPING.chars() {
+            let display = draw(&ch.to_string(), position, style);
+            let drawn = display.pixels().map(|Pixel(point, _)| point).collect::<Vec<_>>();
+            prop_assert_eq!(drawn, cell.points().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn unmapped_chars_draw_the_fallback_glyph(
+        font in size(),
+        ch in unmapped_char(),
+        position in position(),
+    ) {
+        let style = MonoTextStyle::new(font, TEXT_COLOR);
+        let fallback = draw("?", position, style);
+        let unmapped = draw(&ch.to_string(), position, style);
+        prop_assert_eq!(unmapped, fallback);
+    }
+
+    #[test]
+    fn text_width_is_the_sum_of_cells_and_spacing(
+        font in size(),
+        text in line(),
+        position in position(),
+    ) {
+        let expected = Size::new(text_width(font, &text) as u32, font.character_size.height);
+        let style = MonoTextStyleBuilder::new()
+            .font(font)
+            .text_color(TEXT_COLOR)
+            .background_color(BACKGROUND_COLOR)
+            .build();
+
+        let text = Text::with_baseline(&text, position, style, Baseline::Top);
+        prop_assert_eq!(text.bounding_box(), Rectangle::new(position, expected));
+
+        // The background covers the spacing between characters too, and the text continues right
+        // after the last character
+        let mut display = RecordingDisplay::new();
+        let next = text.draw(&mut display).unwrap();
+        prop_assert_eq!(display.pixels().count() as u32, expected.width * expected.height);
+        prop_assert_eq!(next, position + Point::new(expected.width as i32, 0));
+    }
+
+    #[test]
+    fn underline_is_drawn_on_its_rows(font in size(), text in line(), position in position()) {
+        let style = MonoTextStyleBuilder::new()
+            .font(font)
+            .text_color(TEXT_COLOR)
+            .underline_with_color(DECORATION_COLOR)
+            .build();
+        let display = draw(&text, position, style);
+        let expected = Rectangle::new(
+            position + Point::new(0, font.underline.offset as i32),
+            Size::new(text_width(font, &text) as u32, font.underline.height),
+        );
+        prop_assert_eq!(points(&display, DECORATION_COLOR), expected.points().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn strikethrough_is_drawn_on_its_rows(font in size(), text in line(), position in position()) {
+        let style = MonoTextStyleBuilder::new()
+            .font(font)
+            .text_color(TEXT_COLOR)
+            .strikethrough_with_color(DECORATION_COLOR)
+            .build();
+        let display = draw(&text, position, style);
+        let expected = Rectangle::new(
+            position + Point::new(0, font.strikethrough.offset as i32),
+            Size::new(text_width(font, &text) as u32, font.strikethrough.height),
+        );
+        prop_assert_eq!(points(&display, DECORATION_COLOR), expected.points().collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,209 @@
+//! Pixel-exact snapshot tests of every size.
+//!
+//! Each size renders a fixed sample covering every mapped character, a background colour,
+//! underline, strikethrough and multiple lines, which is compared with the golden image in
+//! `tests/snapshots`. When a change to the font data or metrics is intended, regenerate the golden
+//! images with:
+//!
+//! ```text
+//! PROFONT_BLESS=1 cargo test -p profont --test snapshots
+//! ```
+//!
+//! and review the changed PNGs before committing them.
+
+use std::convert::{Infallible, TryFrom};
+use std::env;
+use std::path::PathBuf;
+
+use embedded_graphics::{
+    mono_font::{MonoFont, MonoTextStyle, MonoTextStyleBuilder},
+    pixelcolor::Rgb888,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use image::{Rgb, RgbImage};
+use profont::*;
+
+mod common;
+use common::{mapped_chars, CHARS_PER_LINE};
+
+const BACKGROUND_COLOR: Rgb888 = Rgb888::WHITE;
+const TEXT_COLOR: Rgb888 = Rgb888::BLACK;
+const TEXT_BACKGROUND_COLOR: Rgb888 = Rgb888::new(0xff, 0xe0, 0x80);
+const UNDERLINE_COLOR: Rgb888 = Rgb888::BLUE;
+const STRIKETHROUGH_COLOR: Rgb888 = Rgb888::RED;
+const STYLED_TEXT: &str = "Hello, world!\nÀ bientôt ½ 0O 1lI";
+
+/// An image to draw the sample into.
+struct Canvas(RgbImage);
+
+impl DrawTarget for Canvas {
+    type Color = Rgb888;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Ok((x, y)) = <(u32, u32)>::try_from(point) {
+                if x < self.0.width() && y < self.0.height() {
+                    self.0
+                        .put_pixel(x, y, Rgb([color.r(), color.g(), color.b()]));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Canvas {
+    fn size(&self) -> Size {
+        Size::new(self.0.width(), self.0.height())
+    }
+}
+
+/// Renders every mapped character, then the styled text with a background colour, underline,
+/// strikethrough and all three together.
+fn render(font: &MonoFont) -> RgbImage {
+    let all_chars = mapped_chars();
+
+    let plain = MonoTextStyle::new(font, TEXT_COLOR);
+    let base = MonoTextStyleBuilder::new()
+        .font(font)
+        .text_color(TEXT_COLOR);
+    let styles = [
+        base.background_color(TEXT_BACKGROUND_COLOR).build(),
+        base.underline_with_color(UNDERLINE_COLOR).build(),
+        base.strikethrough_with_color(STRIKETHROUGH_COLOR).build(),
+        base.background_color(TEXT_BACKGROUND_COLOR)
+            .underline_with_color(UNDERLINE_COLOR)
+            .strikethrough_with_color(STRIKETHROUGH_COLOR)
+            .build(),
+    ];
+
+    let cell = font.character_size;
+    let width =
+        CHARS_PER_LINE as u32 * (cell.width + font.character_spacing) - font.character_spacing;
+    let lines = all_chars.lines().count() + styles.len() * STYLED_TEXT.lines().count();
+    let mut canvas = Canvas(RgbImage::from_pixel(
+        width,
+        lines as u32 * cell.height,
+        Rgb([
+            BACKGROUND_COLOR.r(),
+            BACKGROUND_COLOR.g(),
+            BACKGROUND_COLOR.b(),
+        ]),
+    ));
+
+    let mut next = Text::with_baseline(&all_chars, Point::zero(), plain, Baseline::Top)
+        .draw(&mut canvas)
+        .unwrap();
+    for style in styles.iter() {
+        // Start each block on a new line
+        let position = Point::new(0, next.y + cell.height as i32);
+        next = Text::with_baseline(STYLED_TEXT, position, *style, Baseline::Top)
+            .draw(&mut canvas)
+            .unwrap();
+    }
+
+    canvas.0
+}
+
+/// Compares the rendering of `font` with its golden image, or replaces the golden image when
+/// `PROFONT_BLESS` is set.
+fn check(name: &str, font: &MonoFont) {
+    let actual = render(font);
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots");
+    let path = dir.join(format!("{}.png", name));
+    let bless = "run `PROFONT_BLESS=1 cargo test -p profont --test snapshots` to update it";
+
+    if env::var_os("PROFONT_BLESS").is_some() {
+        std::fs::create_dir_all(&dir).unwrap();
+        actual.save(&path).unwrap();
+        return;
+    }
+
+    let expected = match image::open(&path) {
+        Ok(image) => image.into_rgb8(),
+        Err(err) => panic!(
+            "unable to read golden image {}: {}\n{}",
+            path.display(),
+            err,
+            bless
+        ),
+    };
+
+    if expected == actual {
+        return;
+    }
+
+    // Keep the rendering so it can be compared with the golden image
+    let actual_path =
+        PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.actual.png", name));
+    actual.save(&actual_path).unwrap();
+
+    if expected.dimensions() != actual.dimensions() {
+        panic!(
+            "{} is {:?} but the rendering is {:?}, written to {}\nIf this is intended {}",
+            path.display(),
+            expected.dimensions(),
+            actual.dimensions(),
+            actual_path.display(),
+            bless
+        );
+    }
+    let differences = expected
+        .enumerate_pixels()
+        .filter(|(x, y, pixel)| actual.get_pixel(*x, *y) != *pixel)
+        .map(|(x, y, _)| (x, y))
+        .collect::<Vec<_>>();
+    let (x, y) = differences[0];
+    panic!(
+        "{} pixel(s) differ from {}, the first at ({}, {}) is {:?} instead of {:?}\n\
+         The rendering was written to {}\nIf this is intended {}",
+        differences.len(),
+        path.display(),
+        x,
+        y,
+        actual.get_pixel(x, y).0,
+        expected.get_pixel(x, y).0,
+        actual_path.display(),
+        bless
+    );
+}
+
+#[test]
+fn profont_7_point() {
+    check("ProFont7Point", &PROFONT_7_POINT);
+}
+
+#[test]
+fn profont_9_point() {
+    check("ProFont9Point", &PROFONT_9_POINT);
+}
+
+#[test]
+fn profont_10_point() {
+    check("ProFont10Point", &PROFONT_10_POINT);
+}
+
+#[test]
+fn profont_12_point() {
+    check("ProFont12Point", &PROFONT_12_POINT);
+}
+
+#[test]
+fn profont_14_point() {
+    check("ProFont14Point", &PROFONT_14_POINT);
+}
+
+#[test]
+fn profont_18_point() {
+    check("ProFont18Point", &PROFONT_18_POINT);
+}
+
+#[test]
+fn profont_24_point() {
+    check("ProFont24Point", &PROFONT_24_POINT);
+}
@@ -0,0 +1,116 @@
+//! Tests of the draw target and assertions of the `testing` feature.
+
+use embedded_graphics::{
+    mono_font::{MonoTextStyle, MonoTextStyleBuilder},
+    pixelcolor::{BinaryColor, Rgb888},
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+};
+use profont::testing::{self, RecordingDisplay};
+use profont::*;
+
+#[test]
+fn render_draws_every_pixel_of_the_cells() {
+    let style = MonoTextStyleBuilder::new()
+        .font(&PROFONT_7_POINT)
+        .text_color(BinaryColor::On)
+        .background_color(BinaryColor::Off)
+        .build();
+    assert_eq!(
+        testing::render("Hi", style),
+        concat!(
+            "..........\n",
+            "..........\n",
+            "#..#...#..\n",
+            "#..#......\n",
+            "####..##..\n",
+            "#..#...#..\n",
+            "#..#...#..\n",
+            "#..#..###.\n",
+            "..........\n",
+            "..........\n",
+        )
+    );
+}
+
+#[test]
+fn render_includes_spacing_and_lines() {
+    let style = MonoTextStyle::new(&PROFONT_10_POINT, Rgb888::RED);
+    let art = testing::render("ab\nc", style);
+    let lines = art.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 24);
+    assert!(lines.iter().all(|line| line.len() == 13));
+    assert!(art.contains('R'));
+}
+
+#[test]
+fn recording_display_has_no_bounds() {
+    let mut display = RecordingDisplay::new();
+    Rectangle::new(Point::new(-100, 200), Size::new(3, 2))
+        .into_styled(PrimitiveStyle::with_fill(Rgb888::GREEN))
+        .draw(&mut display)
+        .unwrap();
+    Pixel(Point::new(1000, 201), Rgb888::BLUE)
+        .draw(&mut display)
+        .unwrap();
+
+    assert_eq!(
+        display.extent(),
+        Rectangle::with_corners(Point::new(-100, 200), Point::new(1000, 201))
+    );
+    assert_eq!(display.get_pixel(Point::new(-98, 201)), Some(Rgb888::GREEN));
+    assert_eq!(display.get_pixel(Point::new(-97, 201)), None);
+    assert_eq!(display.pixels().count(), 7);
+    assert_eq!(
+        display.ascii_art(&Rectangle::new(Point::new(-101, 200), Size::new(5, 2))),
+        " GGG \n GGG \n"
+    );
+}
+
+#[test]
+fn assert_text_accepts_text_over_other_drawing() {
+    let mut display = RecordingDisplay::new();
+    let style = MonoTextStyle::new(&PROFONT_12_POINT, BinaryColor::On);
+    Rectangle::new(Point::zero(), Size::new(60, 30))
+        .into_styled(PrimitiveStyle::with_fill(BinaryColor::Off))
+        .draw(&mut display)
+        .unwrap();
+    Text::with_baseline("Hello\nworld", Point::new(5, 2), style, Baseline::Top)
+        .draw(&mut display)
+        .unwrap();
+
+    testing::assert_text(&display, "Hello\nworld", Point::new(5, 2), style);
+    testing::assert_text(&display, "world", Point::new(5, 17), style);
+}
+
+#[test]
+#[should_panic(expected = "text \"Hi\" at (1, 0) doesn't match the display")]
+fn assert_text_rejects_text_at_another_position() {
+    let mut display = RecordingDisplay::new();
+    let style = MonoTextStyle::new(&PROFONT_7_POINT, BinaryColor::On);
+    Text::with_baseline("Hi", Point::zero(), style, Baseline::Top)
+        .draw(&mut display)
+        .unwrap();
+
+    testing::assert_text(&display, "Hi", Point::new(1, 0), style);
+}
+
+#[test]
+#[should_panic(expected = "#..#...#..  #..#...#..")]
+fn assert_text_shows_expected_and_actual_pixels() {
+    let style = MonoTextStyleBuilder::new()
+        .font(&PROFONT_7_POINT)
+        .text_color(BinaryColor::On)
+        .background_color(BinaryColor::Off)
+        .build();
+    let mut display = RecordingDisplay::new();
+    Text::with_baseline("Hi", Point::zero(), style, Baseline::Top)
+        .draw(&mut display)
+        .unwrap();
+    Pixel(Point::new(9, 9), BinaryColor::On)
+        .draw(&mut display)
+        .unwrap();
+
+    testing::assert_text(&display, "Hi", Point::zero(), style);
+}
@@ -0,0 +1,248 @@
+use std::char;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context};
+
+/// The set of characters to include in a generated font.
+///
+/// Characters are kept sorted by code point and free of duplicates, which is the order they are
+/// laid out in the sprite sheet.
+#[derive(Debug)]
+pub struct Charset {
+    chars: Vec<char>,
+}
+
+impl Charset {
+    /// The default character set: ASCII from ' ' to '~', then ISO 8859-1 from `&nbsp;` (HTML
+    /// notation) to `ÿ`.
+    pub fn latin1() -> Self {
+        let basic = ' '..='~';
+        let extended = '\u{00A0}'..='ÿ';
+        Charset::from_chars(basic.chain(extended))
+    }
+
+    /// Reads a charset spec file.
+    ///
+    /// Each line of the file is either a list of `U+XXXX` code points and `U+XXXX..U+YYYY`
+    /// inclusive ranges separated by whitespace, or plain text in which case every character on
+    /// the line is included. Empty lines are ignored.
+    pub fn from_file(path: &Path) -> Result<Self, anyhow::Error> {
+        let spec = fs::read_to_string(path)
+            .with_context(|| format!("unable to read charset file {}", path.display()))?;
+        Charset::parse(&spec).with_context(|| format!("invalid charset file {}", path.display()))
+    }
+
+    pub fn parse(spec: &str) -> Result<Self, anyhow::Error> {
+        let mut chars = Vec::new();
+        for (lineno, line) in spec.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+
+            match parse_code_points(line) {
+                Some(ranges) => {
+                    for range in ranges {
+                        let (start, end) =
+                            range.map_err(|err| anyhow!("line {}: {}", lineno + 1, err))?;
+                        chars.extend((start..=end).filter_map(char::from_u32));
+                    }
+                }
+                None => chars.extend(line.chars()),
+            }
+        }
+
+        if chars.contains(&'\0') {
+            bail!("U+0000 can't be included in a charset");
+        }
+        if chars.is_empty() {
+            bail!("charset is empty");
+        }
+
+        Ok(Charset::from_chars(chars))
+    }
+
+    pub fn from_chars(chars: impl IntoIterator<Item = char>) -> Self {
+        let mut chars = chars.into_iter().collect::<Vec<_>>();
+        chars.sort_unstable();
+        chars.dedup();
+        Charset { chars }
+    }
+
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    /// Returns the position of `ch` in the sprite sheet.
+    pub fn index(&self, ch: char) -> Option<usize> {
+        self.chars.binary_search(&ch).ok()
+    }
+
+    /// Encodes the charset as a `StrGlyphMapping` string.
+    ///
+    /// Runs of three or more consecutive characters are written as `\0` followed by the first and
+    /// last character of the run, shorter runs are written out as is.
+    pub fn glyph_mapping(&self) -> String {
+        let mut mapping = String::new();
+        let mut chars = self.chars.iter().copied().peekable();
+        while let Some(start) = chars.next() {
+            let mut end = start;
+            while let Some(&next) = chars.peek() {
+                if next as u32 != end as u32 + 1 {
+                    break;
+                }
+                end = next;
+                chars.next();
+            }
+
+            if end as u32 - start as u32 >= 2 {
+                mapping.push('\0');
+                mapping.push(start);
+                mapping.push(end);
+            } else {
+                mapping.extend((start as u32..=end as u32).filter_map(char::from_u32));
+            }
+        }
+        mapping
+    }
+}
+
+/// Parses a character given either as itself or as a `U+XXXX` code point.
+pub fn parse_char(s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Ok(ch),
+        _ if s.starts_with("U+") => {
+            parse_code_point(s).map(|code_point| char::from_u32(code_point).unwrap())
+        }
+        _ => Err(format!(
+            "invalid character '{}', expected a single character or U+XXXX",
+            s
+        )),
+    }
+}
+
+/// Parses a line of `U+XXXX` and `U+XXXX..U+YYYY` items.
+///
+/// Returns `None` if the line isn't made up entirely of code point items, in which case it should
+/// be treated as plain text.
+fn parse_code_points(line: &str) -> Option<Vec<Result<(u32, u32), String>>> {
+    let items = line.split_whitespace().collect::<Vec<_>>();
+    if items.is_empty() || !items.iter().all(|item| item.starts_with("U+")) {
+        return None;
+    }
+
+    let ranges = items
+        .into_iter()
+        .map(|item| {
+            let (start, end) = match item.find("..") {
+                Some(i) => (&item[..i], &item[i + 2..]),
+                None => (item, item),
+            };
+            let start = parse_code_point(start)?;
+            let end = parse_code_point(end)?;
+            if start > end {
+                return Err(format!("range {} is reversed", item));
+            }
+            Ok((start, end))
+        })
+        .collect();
+    Some(ranges)
+}
+
+fn parse_code_point(item: &str) -> Result<u32, String> {
+    let code_point = item
+        .strip_prefix("U+")
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| format!("invalid code point '{}'", item))?;
+    char::from_u32(code_point)
+        .map(|ch| ch as u32)
+        .ok_or_else(|| format!("{} is not a valid character", item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::mono_font::mapping::{GlyphMapping, StrGlyphMapping};
+
+    #[test]
+    fn plain_lines_include_every_character() {
+        let charset = Charset::parse("cab\n\nba 1\n").unwrap();
+        assert_eq!(charset.chars(), [' ', '1', 'a', 'b', 'c']);
+    }
+
+    #[test]
+    fn code_points_and_ranges() {
+        let charset = Charset::parse("U+0041 U+0030..U+0032\nU+00E9..U+00E9").unwrap();
+        assert_eq!(charset.chars(), ['0', '1', '2', 'A', 'é']);
+    }
+
+    #[test]
+    fn lines_mixing_code_points_and_text_are_text() {
+        let charset = Charset::parse("U+0041 x").unwrap();
+        assert_eq!(charset.chars(), [' ', '+', '0', '1', '4', 'U', 'x']);
+    }
+
+    #[test]
+    fn ranges_skip_surrogates() {
+        let charset = Charset::parse("U+D7FF..U+E000").unwrap();
+        assert_eq!(charset.chars(), ['\u{D7FF}', '\u{E000}']);
+    }
+
+    #[test]
+    fn reversed_ranges_are_rejected() {
+        let err = Charset::parse("abc\nU+0042..U+0041").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: range U+0042..U+0041 is reversed");
+    }
+
+    #[test]
+    fn invalid_code_points_are_rejected() {
+        let err = Charset::parse("U+00ZZ").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: invalid code point 'U+00ZZ'");
+        let err = Charset::parse("U+0041..U+D800").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: U+D800 is not a valid character");
+        let err = Charset::parse("U+0041..").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: invalid code point ''");
+    }
+
+    #[test]
+    fn empty_and_nul_charsets_are_rejected() {
+        assert_eq!(
+            Charset::parse("\n\n").unwrap_err().to_string(),
+            "charset is empty"
+        );
+        assert_eq!(
+            Charset::parse("U+0000..U+0020").unwrap_err().to_string(),
+            "U+0000 can't be included in a charset"
+        );
+    }
+
+    #[test]
+    fn glyph_mapping_matches_index() {
+        let charsets = [
+            Charset::latin1(),
+            Charset::parse("0123456789:").unwrap(),
+            Charset::parse("ab d U+2190..U+2193 U+2603").unwrap(),
+            Charset::parse("x").unwrap(),
+        ];
+        for charset in charsets.iter() {
+            let encoded = charset.glyph_mapping();
+            let mapping = StrGlyphMapping::new(&encoded, charset.len());
+            for &ch in charset.chars() {
+                assert_eq!(
+                    mapping.index(ch),
+                    charset.index(ch).unwrap(),
+                    "{:?} in {:?}",
+                    ch,
+                    encoded
+                );
+            }
+            assert_eq!(charset.index('\u{1F600}'), None);
+            assert_eq!(mapping.index('\u{1F600}'), charset.len());
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use std::ffi::OsString;
+use std::ops::RangeInclusive;
+
+use anyhow::bail;
+use embedded_graphics::{
+    mono_font::MonoTextStyleBuilder,
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use embedded_graphics_simulator::SimulatorDisplay;
+use profont::GLYPH_MAPPING;
+
+use crate::generate;
+use crate::sizes::{FontSize, SIZES};
+
+const CHARS_PER_ROW: u32 = 32;
+/// Lowercase letters without ascenders or descenders, used to measure the x-height.
+const X_HEIGHT_CHARS: &str = "acemnorsuvwxz";
+const HELP_MESSAGE: &str = "\
+profont check-metrics
+
+Checks the MonoFont constants of each size against the font bitmaps. Reports
+decorations outside the character cell, strikethroughs that don't cross the
+x-height, baselines that don't match the source font and raw data of the
+wrong length.
+
+USAGE:
+    check-metrics [OPTIONS]
+
+FLAGS:
+  -h, --help    Prints help information
+";
+
+pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
+    if args.iter().any(|arg| arg.to_str() == Some("--help")) {
+        println!("{}", HELP_MESSAGE);
+        return Ok(());
+    }
+
+    let mut problem_count = 0;
+    for size in SIZES.iter() {
+        let problems = check(size)?;
+        if problems.is_empty() {
+            println!("{}: ok", size.name());
+        } else {
+            println!("{}:", size.name());
+            for problem in &problems {
+                println!("  - {}", problem);
+            }
+        }
+        problem_count += problems.len();
+    }
+
+    if problem_count > 0 {
+        bail!("found {} problem(s)", problem_count);
+    }
+
+    Ok(())
+}
+
+/// Checks a size, returning a description of each problem found.
+pub fn check(size: &FontSize) -> Result<Vec<String>, anyhow::Error> {
+    let font = &size.font;
+    let cell = font.character_size;
+    let mut problems = Vec::new();
+
+    // The raw data must hold exactly enough rows for all of the glyphs
+    let glyph_count = GLYPH_MAPPING.chars().count() as u32;
+    let glyph_rows = (glyph_count + CHARS_PER_ROW - 1) / CHARS_PER_ROW;
+    let expected_len = CHARS_PER_ROW * cell.width * glyph_rows * cell.height / 8;
+    if size.raw.len() != expected_len as usize {
+        problems.push(format!(
+            "raw data is {} bytes, expected {} for {} rows of {}x{} glyphs",
+            size.raw.len(),
+            expected_len,
+            glyph_rows,
+            cell.width,
+            cell.height
+        ));
+    }
+
+    // The cell and baseline must match the strike the size was generated from
+    let metrics = generate::strike_metrics(size.strike)?;
+    let strike_height = (i32::from(metrics.ascender) - i32::from(metrics.descender)) as u32;
+    if cell != Size::new(u32::from(metrics.advance), strike_height) {
+        problems.push(format!(
+            "character size is {}x{}, the {}px strike is {}x{}",
+            cell.width, cell.height, size.strike, metrics.advance, strike_height
+        ));
+    }
+    // The baseline in a MonoFont is the bottom row of the glyphs, which is the last row of the
+    // ascender
+    let expected_baseline = i32::from(metrics.ascender) - 1;
+    if font.baseline as i32 != expected_baseline {
+        problems.push(format!(
+            "baseline is {}, expected {} from the ascender of the {}px strike ({})",
+            font.baseline, expected_baseline, size.strike, metrics.ascender
+        ));
+    }
+
+    // Render the decorations over the glyphs and check where they land
+    let underline = ink_rows(size, " ", MonoTextStyleBuilder::new().underline());
+    match underline {
+        Some(ref rows) if *rows.end() >= cell.height => problems.push(format!(
+            "underline covers {}, outside the {} row cell",
+            describe_rows(rows),
+            cell.height
+        )),
+        Some(_) => {}
+        None => problems.push("underline is not drawn".to_string()),
+    }
+
+    let strikethrough = ink_rows(size, " ", MonoTextStyleBuilder::new().strikethrough());
+    let x_height = ink_rows(size, X_HEIGHT_CHARS, MonoTextStyleBuilder::new());
+    match (strikethrough, x_height) {
+        (Some(strikethrough), Some(x_height)) => {
+            if strikethrough.start() < x_height.start() || strikethrough.end() > x_height.end() {
+                problems.push(format!(
+                    "strikethrough covers {}, which doesn't cross the x-height of lowercase \
+                     letters at {}",
+                    describe_rows(&strikethrough),
+                    describe_rows(&x_height)
+                ));
+            }
+        }
+        (None, _) => problems.push("strikethrough is not drawn".to_string()),
+        (_, None) => problems.push("lowercase letters are blank".to_string()),
+    }
+
+    Ok(problems)
+}
+
+/// Draws `text` and returns the range of rows that have set pixels, relative to the top of the
+/// character cell.
+fn ink_rows(
+    size: &FontSize,
+    text: &str,
+    style: MonoTextStyleBuilder<'_, BinaryColor>,
+) -> Option<RangeInclusive<u32>> {
+    let font = &size.font;
+    let style = style.font(font).text_color(BinaryColor::On).build();
+    let width = text.chars().count() as u32 * (font.character_size.width + font.character_spacing);
+    // Leave room below the cell to catch decorations that are drawn outside of it
+    let height = font.character_size.height * 2;
+
+    let mut display = SimulatorDisplay::<BinaryColor>::new(Size::new(width, height));
+    Text::with_baseline(text, Point::zero(), style, Baseline::Top)
+        .draw(&mut display)
+        .unwrap();
+
+    let mut rows = (0..height).filter(|&y| {
+        (0..width).any(|x| display.get_pixel(Point::new(x as i32, y as i32)) == BinaryColor::On)
+    });
+    let first = rows.next()?;
+    let last = rows.next_back().unwrap_or(first);
+    Some(first..=last)
+}
+
+fn describe_rows(rows: &RangeInclusive<u32>) -> String {
+    if rows.start() == rows.end() {
+        format!("row {}", rows.start())
+    } else {
+        format!("rows {}-{}", rows.start(), rows.end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_sizes_are_consistent() {
+        for size in SIZES.iter() {
+            let problems = check(size).unwrap();
+            // The 24 point baseline is a row too low, which can't be fixed without moving text
+            // drawn with the published constants
+            let known: &[&str] = if size.point == 24 {
+                &["baseline is 24, expected 23 from the ascender of the 28px strike (24)"]
+            } else {
+                &[]
+            };
+            assert_eq!(problems, known, "{}", size.name());
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail};
+use embedded_graphics::{
+    mono_font::{iso_8859_1::*, MonoFont, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+};
+use embedded_graphics_simulator::{
+    OutputSettings, OutputSettingsBuilder, SimulatorDisplay, Window,
+};
+use pico_args::Arguments;
+use profont::PROFONT_9_POINT;
+
+use crate::render;
+use crate::sizes::{self, FontSize, SIZES};
+
+const HELP_MESSAGE: &str = "\
+profont compare
+
+Renders the same text in ProFont and the built-in embedded-graphics fonts with
+a similar cell height, wrapped to the width of a display. Each rendering is
+labelled with its cell size and how many characters fit on a line.
+
+USAGE:
+    compare [OPTIONS] [TEXT]
+
+The built-in fonts are the ISO 8859-1 variants, which cover the same characters
+as ProFont, and have the same cell sizes as the ASCII ones.
+
+FLAGS:
+  -h, --help                Prints help information
+      --no-gui-scaling      Disable GUI scaling by factor 2
+
+OPTIONS:
+      --size        POINTS  Only compare this ProFont size
+      --width       PX      Width of the display to wrap the text to [default: 128]
+  -o, --output      FILE    Write a PNG instead of opening a window
+";
+const SAMPLE_TEXT: &str = "The quick brown fox jumps over the lazy dog. 0123456789 \
+                           (){}[] <=> 0O 1lI| Àéîõü ß";
+/// Built-in fonts are compared with a ProFont size when their cell heights differ by at most
+/// this many pixels.
+const HEIGHT_TOLERANCE: u32 = 2;
+const MARGIN: i32 = 10;
+/// Space between the fonts compared with one ProFont size.
+const GAP: i32 = 6;
+/// Space between the groups of each ProFont size.
+const GROUP_GAP: i32 = 20;
+const BACKGROUND_COLOR: Rgb888 = Rgb888::BLACK;
+const LABEL_COLOR: Rgb888 = Rgb888::CSS_LIGHT_GRAY;
+const PROFONT_LABEL_COLOR: Rgb888 = Rgb888::CSS_GOLD;
+const SCREEN_COLOR: Rgb888 = Rgb888::new(0x18, 0x18, 0x18);
+const INK_COLOR: Rgb888 = Rgb888::WHITE;
+
+/// The regular weight built-in fonts, in order of size.
+const BUILT_IN_FONTS: &[(&str, &MonoFont)] = &[
+    ("FONT_4X6", &FONT_4X6),
+    ("FONT_5X7", &FONT_5X7),
+    ("FONT_5X8", &FONT_5X8),
+    ("FONT_6X9", &FONT_6X9),
+    ("FONT_6X10", &FONT_6X10),
+    ("FONT_6X12", &FONT_6X12),
+    ("FONT_6X13", &FONT_6X13),
+    ("FONT_7X13", &FONT_7X13),
+    ("FONT_7X14", &FONT_7X14),
+    ("FONT_8X13", &FONT_8X13),
+    ("FONT_9X15", &FONT_9X15),
+    ("FONT_9X18", &FONT_9X18),
+    ("FONT_10X20", &FONT_10X20),
+];
+
+struct Args {
+    show_help: bool,
+    text: String,
+    size: Option<u8>,
+    width: u32,
+    output: Option<PathBuf>,
+    gui_scale: u32,
+}
+
+/// A font to render, with its label.
+struct Entry<'a> {
+    label: String,
+    font: &'a MonoFont<'a>,
+    is_profont: bool,
+}
+
+pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
+    let args = parse_args(args)?;
+
+    if args.show_help {
+        println!("{}", HELP_MESSAGE);
+        return Ok(());
+    }
+
+    let selected = match args.size {
+        Some(point) => vec![sizes::find(point)?],
+        None => SIZES.iter().collect(),
+    };
+    let groups = selected
+        .into_iter()
+        .map(|size| group(size, args.width))
+        .collect::<Vec<_>>();
+    let display = draw(&groups, &args.text, args.width);
+
+    match args.output {
+        Some(path) => {
+            display
+                .to_rgb_output_image(&OutputSettings::default())
+                .save_png(&path)?;
+            println!("Wrote {}", path.display());
+        }
+        None => {
+            let output_settings = OutputSettingsBuilder::new().scale(args.gui_scale).build();
+            Window::new("ProFont comparison", &output_settings).show_static(&display);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns a ProFont size followed by the built-in fonts with a similar cell height. If none are
+/// within `HEIGHT_TOLERANCE` the closest one is used.
+fn group(size: &'static FontSize, width: u32) -> Vec<Entry<'static>> {
+    let height = size.font.character_size.height;
+    let difference = |font: &MonoFont| (font.character_size.height as i32 - height as i32).abs();
+    let mut similar = BUILT_IN_FONTS
+        .iter()
+        .filter(|(_, font)| difference(font) <= HEIGHT_TOLERANCE as i32)
+        .collect::<Vec<_>>();
+    if similar.is_empty() {
+        similar.extend(
+            BUILT_IN_FONTS
+                .iter()
+                .min_by_key(|(_, font)| difference(font)),
+        );
+    }
+
+    let mut entries = vec![Entry {
+        label: label(&format!("ProFont {}pt", size.point), &size.font, width),
+        font: &size.font,
+        is_profont: true,
+    }];
+    entries.extend(similar.into_iter().map(|(name, font)| Entry {
+        label: label(name, font, width),
+        font,
+        is_profont: false,
+    }));
+    entries
+}
+
+fn label(name: &str, font: &MonoFont, width: u32) -> String {
+    format!(
+        "{}  {}x{} cell, {} characters per {}px line",
+        name,
+        font.character_size.width,
+        font.character_size.height,
+        render::columns_for_width(font, width),
+        width
+    )
+}
+
+/// Draws each font with its label above a screen of `width` pixels showing the wrapped text.
+fn draw(groups: &[Vec<Entry>], text: &str, width: u32) -> SimulatorDisplay<Rgb888> {
+    let label_height = PROFONT_9_POINT.character_size.height as i32;
+
+    // Wrap the text for every font up front, to work out the size of the image
+    let wrapped = groups
+        .iter()
+        .map(|entries| {
+            entries
+                .iter()
+                .map(|entry| {
+                    let columns = render::columns_for_width(entry.font, width);
+                    render::wrap(text, Some(columns))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let screen_height = |entry: &Entry, lines: &[String]| {
+        (entry.font.character_size.height as usize * lines.len().max(1)) as i32
+    };
+
+    let label_width = groups
+        .iter()
+        .flatten()
+        .map(|entry| render::text_width(&PROFONT_9_POINT, entry.label.chars().count()))
+        .max()
+        .unwrap_or(0);
+    let image_width = MARGIN * 2 + width.max(label_width) as i32;
+    let image_height = MARGIN * 2
+        + groups
+            .iter()
+            .zip(&wrapped)
+            .map(|(entries, wrapped)| {
+                entries
+                    .iter()
+                    .zip(wrapped)
+                    .map(|(entry, lines)| label_height + screen_height(entry, lines) + GAP * 2)
+                    .sum::<i32>()
+            })
+            .sum::<i32>()
+        + GROUP_GAP * (groups.len() as i32 - 1);
+    let mut display =
+        SimulatorDisplay::<Rgb888>::new(Size::new(image_width as u32, image_height as u32));
+    display.clear(BACKGROUND_COLOR).unwrap();
+
+    let mut top = MARGIN;
+    for (entries, wrapped) in groups.iter().zip(&wrapped) {
+        for (entry, lines) in entries.iter().zip(wrapped) {
+            let label_color = if entry.is_profont {
+                PROFONT_LABEL_COLOR
+            } else {
+                LABEL_COLOR
+            };
+            Text::with_baseline(
+                &entry.label,
+                Point::new(MARGIN, top),
+                MonoTextStyle::new(&PROFONT_9_POINT, label_color),
+                Baseline::Top,
+            )
+            .draw(&mut display)
+            .unwrap();
+            top += label_height + GAP;
+
+            let height = screen_height(entry, lines);
+            Rectangle::new(Point::new(MARGIN, top), Size::new(width, height as u32))
+                .into_styled(PrimitiveStyle::with_fill(SCREEN_COLOR))
+                .draw(&mut display)
+                .unwrap();
+            Text::with_baseline(
+                &lines.join("\n"),
+                Point::new(MARGIN, top),
+                MonoTextStyle::new(entry.font, INK_COLOR),
+                Baseline::Top,
+            )
+            .draw(&mut display)
+            .unwrap();
+            top += height + GAP;
+        }
+        top += GROUP_GAP;
+    }
+
+    display
+}
+
+fn parse_args(args: Vec<OsString>) -> Result<Args, anyhow::Error> {
+    let mut args = Arguments::from_vec(args);
+
+    let show_help = args.contains(["-h", "--help"]);
+    let no_gui_scaling = args.contains("--no-gui-scaling");
+    let size = args.opt_value_from_str("--size")?;
+    let width = args.opt_value_from_str("--width")?.unwrap_or(128);
+    let output = args.opt_value_from_os_str(["-o", "--output"], parse_path)?;
+
+    let mut free_args = args.finish();
+    let text = match free_args.len() {
+        0 => SAMPLE_TEXT.to_string(),
+        1 => free_args
+            .pop()
+            .unwrap()
+            .into_string()
+            .map_err(|_| anyhow!("TEXT is not valid UTF-8"))?,
+        _ => bail!("Unknown arguments. Use '--help' for help on usage."),
+    };
+
+    Ok(Args {
+        show_help,
+        text,
+        size,
+        width,
+        output,
+        gui_scale: if no_gui_scaling { 1 } else { 2 },
+    })
+}
+
+fn parse_path(s: &OsStr) -> Result<PathBuf, &'static str> {
+    Ok(s.into())
+}
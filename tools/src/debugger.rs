@@ -1,16 +1,67 @@
 use std::ffi::OsString;
+#[cfg(feature = "sdl")]
+use std::path::PathBuf;
+#[cfg(feature = "sdl")]
+use std::thread;
+#[cfg(feature = "sdl")]
+use std::time::Duration;
 
 use embedded_graphics::{
+    mono_font::{MonoFont, MonoTextStyle},
     pixelcolor::Rgb888,
     prelude::*,
-    primitives::{Line, PrimitiveStyle},
-    text::{Text, TextStyle},
+    primitives::{Line, PrimitiveStyle, Rectangle},
+    text::{Baseline, Text, TextStyle},
+};
+#[cfg(feature = "sdl")]
+use embedded_graphics_simulator::{
+    sdl2::{Keycode, Mod},
+    OutputSettings, SimulatorEvent,
 };
 use embedded_graphics_simulator::{OutputSettingsBuilder, SimulatorDisplay, Window};
-use profont::*;
+use profont::PROFONT_9_POINT;
 
+use crate::sizes::{FontSize, SIZES};
 use crate::style;
 
+const KEYS_HELP: &str = "\
+KEYS:
+  Up, Down              Cycle through the sizes, or all sizes at once
+  Page Up, Page Down    Zoom in and out
+  Ctrl+U                Toggle underline
+  Ctrl+S                Toggle strikethrough
+  Ctrl+B                Toggle background colour
+  Ctrl+G                Toggle the character cell grid
+  Ctrl+L                Toggle baseline and decoration guides
+  Ctrl+P                Save the current frame to profont-debugger-N.png
+  Escape                Quit
+
+Any other key edits the sample text.
+";
+const DISPLAY_SIZE: Size = Size::new(800, 640);
+#[cfg(feature = "sdl")]
+const MAX_ZOOM: u32 = 8;
+/// How long to wait between checks for input, so the window updates about 60 times a second.
+#[cfg(feature = "sdl")]
+const FRAME_TIME: Duration = Duration::from_millis(16);
+const SAMPLE_TEXT: &str = "ProFont jpyJPY HiMw!\n¡¢£¤¥¦§¨©ª«¬\u{ad}®¯°±²³´µ¶·¸¹º»¼½¾¿\nÀÁÂÃÄÅÆÇÈÉÊËÌÍÎÏÐÑÒÓÔÕÖ×ØÙÚÛÜÝÞ\nßàáâãäåæçèéêëìíîïðñòóôõö÷øùúûüýþÿ\u{ffff}";
+const BACKGROUND_COLOR: Rgb888 = Rgb888::CSS_DARK_SLATE_BLUE;
+const GRID_COLOR: Rgb888 = Rgb888::new(0x50, 0x50, 0x50);
+const BASELINE_COLOR: Rgb888 = Rgb888::RED;
+const STATUS_COLOR: Rgb888 = Rgb888::CSS_LIGHT_GRAY;
+
+struct Debugger {
+    /// Index of the size being shown, or `None` to show all sizes.
+    size: Option<usize>,
+    zoom: u32,
+    /// Decoration toggles, along with the window scale.
+    style: style::StyleArgs,
+    background: bool,
+    grid: bool,
+    guides: bool,
+    text: String,
+}
+
 pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
     let (args, _free_args) = match style::parse_args(args) {
         Ok(args) => args,
@@ -22,55 +73,365 @@ pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
 
     if args.show_help {
         println!(
-            "{}",
+            "{}\n{}",
             style::help(
                 "debugger",
                 "Renders all characters in all sizes for debugging purposes"
-            )
+            ),
+            KEYS_HELP
         );
         return Ok(());
     }
 
-    let mut display: SimulatorDisplay<Rgb888> = SimulatorDisplay::new(Size::new(800, 640));
-    let character_style = style::builder(&args);
-    let text_style = TextStyle::default();
+    let debugger = Debugger {
+        size: None,
+        zoom: 1,
+        style: args,
+        background: false,
+        grid: false,
+        guides: true,
+        text: SAMPLE_TEXT.to_string(),
+    };
+
+    let output_settings = OutputSettingsBuilder::new()
+        .scale(u32::from(debugger.style.gui_scale))
+        .build();
+    let window = Window::new("ProFont Debugger", &output_settings);
+
+    run(debugger, window)
+}
+
+#[cfg(feature = "sdl")]
+fn run(mut debugger: Debugger, mut window: Window) -> Result<(), anyhow::Error> {
+    let mut display = debugger.draw();
+
+    loop {
+        window.update(&display);
+
+        let mut changed = false;
+        for event in window.events() {
+            match event {
+                SimulatorEvent::Quit
+                | SimulatorEvent::KeyDown {
+                    keycode: Keycode::Escape,
+                    ..
+                } => return Ok(()),
+                SimulatorEvent::KeyDown {
+                    keycode: Keycode::P,
+                    keymod,
+                    ..
+                } if is_ctrl(keymod) => {
+                    let path = screenshot_path();
+                    display
+                        .to_rgb_output_image(&OutputSettings::default())
+                        .save_png(&path)?;
+                    println!("Saved {}", path.display());
+                }
+                SimulatorEvent::KeyDown {
+                    keycode, keymod, ..
+                } => changed |= debugger.handle_key(keycode, keymod),
+                _ => {}
+            }
+        }
+
+        if changed {
+            display = debugger.draw();
+        }
+        thread::sleep(FRAME_TIME);
+    }
+}
+
+/// Without SDL the frame can still be captured with the `EG_SIMULATOR_DUMP` environment variable.
+#[cfg(not(feature = "sdl"))]
+fn run(debugger: Debugger, mut window: Window) -> Result<(), anyhow::Error> {
+    window.show_static(&debugger.draw());
+    Ok(())
+}
+
+impl Debugger {
+    /// Draws the sample text, zoomed and with the enabled overlays, above a status line.
+    fn draw(&self) -> SimulatorDisplay<Rgb888> {
+        let status_height = PROFONT_9_POINT.character_size.height + 4;
+        let view_size = Size::new(
+            DISPLAY_SIZE.width / self.zoom,
+            (DISPLAY_SIZE.height - status_height) / self.zoom,
+        );
+
+        // Draw the text at its actual size, noting where each block of text ends up
+        let mut view = SimulatorDisplay::<Rgb888>::new(view_size);
+        let mut blocks = Vec::new();
+        let mut position = Point::new(10, 10);
+        for size in self.sizes() {
+            let font = &size.font;
+            let text_style = TextStyle::with_baseline(Baseline::Top);
+            Text::with_text_style(&self.text, position, self.character_style(font), text_style)
+                .draw(&mut view)
+                .unwrap();
+
+            blocks.push((font, position));
+            let lines = self.text.split('\n').count() as u32;
+            position += font.character_size.y_axis() * lines;
+            position.y += font.character_size.height as i32 * 2;
+        }
+
+        // Scale it up to the display, then draw the overlays on top
+        let mut display = SimulatorDisplay::<Rgb888>::new(DISPLAY_SIZE);
+        display
+            .draw_iter(view.bounding_box().points().flat_map(|point| {
+                let color = view.get_pixel(point);
+                Rectangle::new(point * self.zoom as i32, Size::new_equal(self.zoom))
+                    .points()
+                    .map(move |p| Pixel(p, color))
+            }))
+            .unwrap();
+
+        for (font, origin) in blocks {
+            if self.grid {
+                self.draw_grid(font, origin, &mut display);
+            }
+            if self.guides {
+                self.draw_guides(font, origin, &mut display);
+            }
+        }
+
+        self.draw_status(&mut display);
+
+        display
+    }
+
+    fn sizes(&self) -> impl Iterator<Item = &'static FontSize> {
+        let selected = self.size;
+        SIZES
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| selected.map_or(true, |selected| selected == *i))
+            .map(|(_, size)| size)
+    }
+
+    fn character_style<'a>(&self, font: &'a MonoFont) -> MonoTextStyle<'a, Rgb888> {
+        let mut builder = style::builder(&self.style).font(font);
+        if self.background {
+            builder = builder.background_color(BACKGROUND_COLOR);
+        }
+        builder.build()
+    }
+
+    /// Returns the number of lines and the length of the longest line of the sample text.
+    fn text_extent(&self) -> (u32, u32) {
+        let lines = self.text.split('\n');
+        let columns = lines.clone().map(|line| line.chars().count()).max();
+        (lines.count() as u32, columns.unwrap_or(0) as u32)
+    }
+
+    /// Outlines the character cells of the sample text.
+    fn draw_grid(&self, font: &MonoFont, origin: Point, display: &mut SimulatorDisplay<Rgb888>) {
+        let (lines, columns) = self.text_extent();
+        let pitch = (font.character_size.width + font.character_spacing) as i32;
+        let height = font.character_size.height as i32;
+        let zoom = self.zoom as i32;
+        let style = PrimitiveStyle::with_stroke(GRID_COLOR, 1);
+
+        let top = origin.y * zoom;
+        let bottom = (origin.y + lines as i32 * height) * zoom;
+        for column in 0..=columns as i32 {
+            let left = origin.x + column * pitch;
+            let mut edges = vec![left];
+            if font.character_spacing > 0 && column < columns as i32 {
+                edges.push(left + font.character_size.width as i32);
+            }
+            for x in edges {
+                Line::new(Point::new(x * zoom, top), Point::new(x * zoom, bottom))
+                    .into_styled(style)
+                    .draw(display)
+                    .unwrap();
+            }
+        }
 
-    let sizes = [
-        (PROFONT_7_POINT, 7),
-        (PROFONT_9_POINT, 9),
-        (PROFONT_10_POINT, 10),
-        (PROFONT_12_POINT, 12),
-        (PROFONT_14_POINT, 14),
-        (PROFONT_18_POINT, 18),
-        (PROFONT_24_POINT, 24),
-    ];
+        let left = origin.x * zoom;
+        let right = (origin.x + columns as i32 * pitch) * zoom;
+        for line in 0..=lines as i32 {
+            let y = (origin.y + line * height) * zoom;
+            Line::new(Point::new(left, y), Point::new(right, y))
+                .into_styled(style)
+                .draw(display)
+                .unwrap();
+        }
+    }
 
-    let mut position = Point::new(10, 10);
+    /// Marks the baseline and the top of the decorations on each line of the sample text.
+    fn draw_guides(&self, font: &MonoFont, origin: Point, display: &mut SimulatorDisplay<Rgb888>) {
+        let (lines, columns) = self.text_extent();
+        let pitch = (font.character_size.width + font.character_spacing) as i32;
+        let zoom = self.zoom as i32;
+        let left = origin.x * zoom;
+        let right = (origin.x + columns as i32 * pitch) * zoom;
 
-    for (font, size) in sizes.iter() {
-        let character_style = character_style.font(font).build();
+        for line in 0..lines as i32 {
+            let top = origin.y + line * font.character_size.height as i32;
+            let guides = [
+                // Along the bottom of the baseline row
+                (top + font.baseline as i32 + 1, -1, BASELINE_COLOR),
+                (
+                    top + font.underline.offset as i32,
+                    0,
+                    Rgb888::CSS_CORNFLOWER_BLUE,
+                ),
+                (
+                    top + font.strikethrough.offset as i32,
+                    0,
+                    Rgb888::CSS_TOMATO,
+                ),
+            ];
+            for &(y, adjust, color) in guides.iter() {
+                let y = y * zoom + adjust;
+                Line::new(Point::new(left, y), Point::new(right, y))
+                    .into_styled(PrimitiveStyle::with_stroke(color, 1))
+                    .draw(display)
+                    .unwrap();
+            }
+        }
+    }
 
-        let test_text  = format!("ProFont {} jpyJPY {} HiMw!\n¡¢£¤¥¦§¨©ª«¬­®¯°±²³´µ¶·¸¹º»¼½¾¿\nÀÁÂÃÄÅÆÇÈÉÊËÌÍÎÏÐÑÒÓÔÕÖ×ØÙÚÛÜÝÞ\nßàáâãäåæçèéêëìíîïðñòóôõö÷øùúûüýþÿ\u{ffff}", size, font.character_size);
+    fn draw_status(&self, display: &mut SimulatorDisplay<Rgb888>) {
+        let on_off = |enabled| if enabled { "on" } else { "off" };
+        let size = match self.size {
+            Some(index) => {
+                let size = &SIZES[index];
+                format!(
+                    "{}pt {}x{}",
+                    size.point, size.font.character_size.width, size.font.character_size.height
+                )
+            }
+            None => "all sizes".to_string(),
+        };
+        let status = format!(
+            "{} | zoom {}x | ^U underline {} | ^S strike {} | ^B bg {} | ^G grid {} | ^L guides {} | ^P save",
+            size,
+            self.zoom,
+            on_off(self.style.underline),
+            on_off(self.style.strikethrough),
+            on_off(self.background),
+            on_off(self.grid),
+            on_off(self.guides)
+        );
 
-        // Draw the font baseline behind the first line of text
-        Line::new(
-            position.y_axis(),
-            position.y_axis() + display.bounding_box().size.x_axis(),
+        let status_height = PROFONT_9_POINT.character_size.height + 4;
+        let top = (DISPLAY_SIZE.height - status_height) as i32;
+        Rectangle::new(
+            Point::new(0, top),
+            Size::new(DISPLAY_SIZE.width, status_height),
         )
-        .into_styled(PrimitiveStyle::with_stroke(Rgb888::RED, 1))
-        .draw(&mut display)?;
+        .into_styled(PrimitiveStyle::with_fill(Rgb888::BLACK))
+        .draw(display)
+        .unwrap();
+        Text::with_baseline(
+            &status,
+            Point::new(4, top + 2),
+            MonoTextStyle::new(&PROFONT_9_POINT, STATUS_COLOR),
+            Baseline::Top,
+        )
+        .draw(display)
+        .unwrap();
+    }
+
+    /// Applies a key press, returning `true` if the frame needs to be redrawn.
+    #[cfg(feature = "sdl")]
+    fn handle_key(&mut self, keycode: Keycode, keymod: Mod) -> bool {
+        if is_ctrl(keymod) {
+            let toggle = match keycode {
+                Keycode::U => &mut self.style.underline,
+                Keycode::S => &mut self.style.strikethrough,
+                Keycode::B => &mut self.background,
+                Keycode::G => &mut self.grid,
+                Keycode::L => &mut self.guides,
+                _ => return false,
+            };
+            *toggle = !*toggle;
+            return true;
+        }
 
-        Text::with_text_style(&test_text, position, character_style, text_style)
-            .draw(&mut display)?;
+        match keycode {
+            Keycode::Up => {
+                self.size = match self.size {
+                    None => Some(SIZES.len() - 1),
+                    Some(0) => None,
+                    Some(index) => Some(index - 1),
+                }
+            }
+            Keycode::Down => {
+                self.size = match self.size {
+                    None => Some(0),
+                    Some(index) if index + 1 == SIZES.len() => None,
+                    Some(index) => Some(index + 1),
+                }
+            }
+            Keycode::PageUp => self.zoom = (self.zoom + 1).min(MAX_ZOOM),
+            Keycode::PageDown => self.zoom = (self.zoom - 1).max(1),
+            Keycode::Backspace => {
+                self.text.pop();
+            }
+            Keycode::Return | Keycode::KpEnter => self.text.push('\n'),
+            _ => match typed_char(keycode, keymod) {
+                Some(ch) => self.text.push(ch),
+                None => return false,
+            },
+        }
 
-        position += font.character_size.y_axis() * test_text.lines().count() as u32;
-        position.y += font.character_size.height as i32 * 2;
+        true
     }
+}
 
-    let output_settings = OutputSettingsBuilder::new()
-        .scale(u32::from(args.gui_scale))
-        .build();
-    Window::new("ProFont Debugger", &output_settings).show_static(&display);
+#[cfg(feature = "sdl")]
+fn is_ctrl(keymod: Mod) -> bool {
+    keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD)
+}
 
-    Ok(())
+/// Maps a key press to the character it types on a US keyboard layout.
+#[cfg(feature = "sdl")]
+fn typed_char(keycode: Keycode, keymod: Mod) -> Option<char> {
+    // SDL key codes for printable keys are the ASCII code of the unshifted character
+    let code = keycode as i32;
+    if !(0x20..0x7f).contains(&code) {
+        return None;
+    }
+    let ch = code as u8 as char;
+
+    if !keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+        return Some(ch);
+    }
+    let shifted = match ch {
+        'a'..='z' => ch.to_ascii_uppercase(),
+        '1' => '!',
+        '2' => '@',
+        '3' => '#',
+        '4' => '$',
+        '5' => '%',
+        '6' => '^',
+        '7' => '&',
+        '8' => '*',
+        '9' => '(',
+        '0' => ')',
+        '-' => '_',
+        '=' => '+',
+        '[' => '{',
+        ']' => '}',
+        '\\' => '|',
+        ';' => ':',
+        '\'' => '"',
+        ',' => '<',
+        '.' => '>',
+        '/' => '?',
+        '`' => '~',
+        _ => ch,
+    };
+    Some(shifted)
+}
+
+/// Returns the first `profont-debugger-N.png` in the current directory that doesn't exist yet.
+#[cfg(feature = "sdl")]
+fn screenshot_path() -> PathBuf {
+    (1..)
+        .map(|n| PathBuf::from(format!("profont-debugger-{}.png", n)))
+        .find(|path| !path.exists())
+        .unwrap()
 }
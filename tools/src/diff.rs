@@ -0,0 +1,358 @@
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context};
+use embedded_graphics::{
+    mono_font::MonoTextStyle,
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+};
+use embedded_graphics_simulator::{OutputSettings, SimulatorDisplay};
+use image::GrayImage;
+use pico_args::Arguments;
+use profont::{GLYPH_MAPPING, PROFONT_9_POINT};
+
+use crate::generate::{self, CHARS_PER_ROW};
+use crate::glyph_art::Glyph;
+use crate::raw;
+use crate::render;
+use crate::sizes::{self, FontSize, SIZES};
+
+const HELP_MESSAGE: &str = "\
+profont diff
+
+Compares two versions of the sprite sheets and lists the characters whose
+glyphs changed in each size. With --output-dir a PNG of the changed glyphs is
+written for each size, showing added pixels in green and removed pixels in red.
+
+USAGE:
+    diff [OPTIONS] OLD NEW
+
+OLD and NEW are either two directories containing ProFont<N>Point.raw or .png
+files, or two sheet files of the same size. .raw files are preferred when a
+directory has both. The size of two sheet files is taken from their names
+unless --size is given.
+
+FLAGS:
+  -h, --help                Prints help information
+
+OPTIONS:
+  -o, --output-dir  DIR     Write ProFont<N>PointDiff.png files to DIR
+      --size        POINTS  Size of the sheets, or the only size to compare in
+                            directories
+      --zoom        N       Size of each glyph pixel in the PNGs [default: 6]
+";
+const MARGIN: i32 = 10;
+const GAP: i32 = 12;
+/// Changed glyphs drawn per row of the PNG.
+const GLYPHS_PER_ROW: usize = 16;
+const BACKGROUND_COLOR: Rgb888 = Rgb888::WHITE;
+const TEXT_COLOR: Rgb888 = Rgb888::new(0x40, 0x40, 0x40);
+const CELL_COLOR: Rgb888 = Rgb888::new(0xf0, 0xf0, 0xf0);
+const INK_COLOR: Rgb888 = Rgb888::BLACK;
+const ADDED_COLOR: Rgb888 = Rgb888::new(0x20, 0xa0, 0x40);
+const REMOVED_COLOR: Rgb888 = Rgb888::new(0xe0, 0x30, 0x30);
+
+struct Args {
+    show_help: bool,
+    old: PathBuf,
+    new: PathBuf,
+    output_dir: Option<PathBuf>,
+    size: Option<u8>,
+    zoom: u32,
+}
+
+/// A glyph that differs between the two versions.
+struct Change {
+    ch: char,
+    old: Glyph,
+    new: Glyph,
+}
+
+pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
+    let args = parse_args(args)?;
+
+    if args.show_help {
+        println!("{}", HELP_MESSAGE);
+        return Ok(());
+    }
+
+    // Pairs of sheets to compare, with the size they belong to
+    let mut pairs = Vec::new();
+    let selected = args.size.map(sizes::find).transpose()?;
+    if args.old.is_dir() && args.new.is_dir() {
+        let sizes = match selected {
+            Some(size) => vec![size],
+            None => SIZES.iter().collect(),
+        };
+        for size in sizes {
+            match (
+                find_sheet(&args.old, &size.name()),
+                find_sheet(&args.new, &size.name()),
+            ) {
+                (Some(old), Some(new)) => pairs.push((size, old, new)),
+                (Some(_), None) => println!("{}: only in {}", size.name(), args.old.display()),
+                (None, Some(_)) => println!("{}: only in {}", size.name(), args.new.display()),
+                (None, None) => {}
+            }
+        }
+    } else if args.old.is_file() && args.new.is_file() {
+        let size = match selected {
+            Some(size) => size,
+            None => {
+                let size = size_for_path(&args.old)?;
+                if size.point != size_for_path(&args.new)?.point {
+                    bail!(
+                        "{} and {} are sheets of different sizes",
+                        args.old.display(),
+                        args.new.display()
+                    );
+                }
+                size
+            }
+        };
+        pairs.push((size, args.old.clone(), args.new.clone()));
+    } else {
+        bail!("OLD and NEW must both be directories or both be files");
+    }
+
+    for (size, old_path, new_path) in pairs {
+        let old = load_sheet(&old_path, size)?;
+        let new = load_sheet(&new_path, size)?;
+        let changes = compare(&old, &new, size);
+
+        if changes.is_empty() {
+            println!("{}: unchanged", size.name());
+            continue;
+        }
+        let chars = changes
+            .iter()
+            .map(|change| format!("U+{:04X}", change.ch as u32))
+            .collect::<Vec<_>>();
+        println!(
+            "{}: {} changed: {}",
+            size.name(),
+            changes.len(),
+            chars.join(" ")
+        );
+
+        if let Some(dir) = &args.output_dir {
+            let path = dir.join(format!("{}Diff.png", size.name()));
+            draw(size, &changes, args.zoom)
+                .to_rgb_output_image(&OutputSettings::default())
+                .save_png(&path)
+                .with_context(|| format!("unable to write {}", path.display()))?;
+            println!("Wrote {}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the `.raw` or `.png` sheet of the named size in `dir`, if there is one.
+fn find_sheet(dir: &Path, name: &str) -> Option<PathBuf> {
+    ["raw", "png"]
+        .iter()
+        .map(|extension| dir.join(format!("{}.{}", name, extension)))
+        .find(|path| path.is_file())
+}
+
+/// Works out the size of a sheet from its file name, e.g. `ProFont12Point.raw`.
+fn size_for_path(path: &Path) -> Result<&'static FontSize, anyhow::Error> {
+    let stem = path.file_stem().and_then(OsStr::to_str).unwrap_or("");
+    SIZES
+        .iter()
+        .find(|size| size.name() == stem)
+        .ok_or_else(|| {
+            anyhow!(
+                "unable to tell the size of {}, name it like ProFont12Point.raw or use --size",
+                path.display()
+            )
+        })
+}
+
+/// Reads a sheet from a PNG, or unpacks it from the 1 bit per pixel `.raw` format using the
+/// cell width of `size`.
+fn load_sheet(path: &Path, size: &FontSize) -> Result<GrayImage, anyhow::Error> {
+    let width = CHARS_PER_ROW as u32 * size.font.character_size.width;
+    if path.extension() == Some(OsStr::new("png")) {
+        let sheet = image::open(path)
+            .with_context(|| format!("unable to read {}", path.display()))?
+            .into_luma8();
+        if sheet.width() != width {
+            bail!(
+                "{} is {} pixels wide, expected {} for {} pixel wide cells",
+                path.display(),
+                sheet.width(),
+                width,
+                size.font.character_size.width
+            );
+        }
+        return Ok(sheet);
+    }
+
+    let raw = fs::read(path).with_context(|| format!("unable to read {}", path.display()))?;
+    let bytes_per_row = ((width + 7) / 8) as usize;
+    if raw.len() % bytes_per_row != 0 {
+        bail!(
+            "{} is {} bytes, which isn't a whole number of {} byte rows",
+            path.display(),
+            raw.len(),
+            bytes_per_row
+        );
+    }
+    Ok(raw::unpack(&raw, width))
+}
+
+/// Compares the glyph of every mapped character. Cells beyond the end of a sheet are blank.
+fn compare(old: &GrayImage, new: &GrayImage, size: &FontSize) -> Vec<Change> {
+    GLYPH_MAPPING
+        .chars()
+        .enumerate()
+        .filter_map(|(index, ch)| {
+            let old = cell(old, index, ch, size);
+            let new = cell(new, index, ch, size);
+            if old.pixels == new.pixels {
+                None
+            } else {
+                Some(Change { ch, old, new })
+            }
+        })
+        .collect()
+}
+
+fn cell(sheet: &GrayImage, index: usize, ch: char, size: &FontSize) -> Glyph {
+    let cell = size.font.character_size;
+    let (x, y) = generate::cell_origin(index, cell.width, cell.height);
+    if y + cell.height > sheet.height() {
+        return Glyph {
+            ch,
+            width: cell.width,
+            height: cell.height,
+            pixels: vec![false; (cell.width * cell.height) as usize],
+        };
+    }
+    Glyph::from_sheet(ch, sheet, x, y, cell.width, cell.height)
+}
+
+/// Draws the changed glyphs enlarged, with the pixels that were added or removed coloured.
+fn draw(size: &FontSize, changes: &[Change], zoom: u32) -> SimulatorDisplay<Rgb888> {
+    let text_style = MonoTextStyle::new(&PROFONT_9_POINT, TEXT_COLOR);
+    let line_height = PROFONT_9_POINT.character_size.height as i32;
+    let cell = size.font.character_size;
+    let title = format!("{}: {} changed", size.name(), changes.len());
+    let legend = "black: unchanged   green: added   red: removed";
+
+    let panel_width = (cell.width * zoom).max(render::text_width(&PROFONT_9_POINT, 6)) as i32;
+    let panel_height = (cell.height * zoom) as i32 + line_height + 4;
+    let columns = changes.len().min(GLYPHS_PER_ROW) as i32;
+    let rows = ((changes.len() + GLYPHS_PER_ROW - 1) / GLYPHS_PER_ROW) as i32;
+
+    let width = (MARGIN * 2 + columns * panel_width + (columns - 1) * GAP)
+        .max(MARGIN * 2 + render::text_width(&PROFONT_9_POINT, legend.len()) as i32);
+    let height = MARGIN * 2 + line_height * 4 + rows * panel_height + (rows - 1) * GAP;
+    let mut display = SimulatorDisplay::<Rgb888>::new(Size::new(width as u32, height as u32));
+    display.clear(BACKGROUND_COLOR).unwrap();
+
+    Text::with_baseline(
+        &title,
+        Point::new(MARGIN, MARGIN),
+        text_style,
+        Baseline::Top,
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    let top = MARGIN + line_height * 2;
+    for (i, change) in changes.iter().enumerate() {
+        let origin = Point::new(
+            MARGIN + (i % GLYPHS_PER_ROW) as i32 * (panel_width + GAP),
+            top + (i / GLYPHS_PER_ROW) as i32 * (panel_height + GAP),
+        );
+        Rectangle::new(origin, cell * zoom)
+            .into_styled(PrimitiveStyle::with_fill(CELL_COLOR))
+            .draw(&mut display)
+            .unwrap();
+        for y in 0..cell.height {
+            for x in 0..cell.width {
+                let color = match (change.old.pixel(x, y), change.new.pixel(x, y)) {
+                    (true, true) => INK_COLOR,
+                    (false, true) => ADDED_COLOR,
+                    (true, false) => REMOVED_COLOR,
+                    (false, false) => continue,
+                };
+                Rectangle::new(
+                    origin + Point::new((x * zoom) as i32, (y * zoom) as i32),
+                    Size::new_equal(zoom),
+                )
+                .into_styled(PrimitiveStyle::with_fill(color))
+                .draw(&mut display)
+                .unwrap();
+            }
+        }
+
+        Text::with_baseline(
+            &format!("U+{:04X}", change.ch as u32),
+            origin + Point::new(0, (cell.height * zoom) as i32 + 4),
+            text_style,
+            Baseline::Top,
+        )
+        .draw(&mut display)
+        .unwrap();
+    }
+
+    Text::with_baseline(
+        legend,
+        Point::new(MARGIN, height - MARGIN - line_height),
+        text_style,
+        Baseline::Top,
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    display
+}
+
+fn parse_args(args: Vec<OsString>) -> Result<Args, anyhow::Error> {
+    let mut args = Arguments::from_vec(args);
+
+    let show_help = args.contains(["-h", "--help"]);
+    let output_dir = args.opt_value_from_os_str(["-o", "--output-dir"], parse_path)?;
+    let size = args.opt_value_from_str("--size")?;
+    let zoom = args.opt_value_from_str("--zoom")?.unwrap_or(6);
+    if zoom == 0 {
+        bail!("--zoom must be at least 1");
+    }
+
+    let free_args = args.finish();
+    if show_help {
+        return Ok(Args {
+            show_help,
+            old: PathBuf::new(),
+            new: PathBuf::new(),
+            output_dir,
+            size,
+            zoom,
+        });
+    }
+    let (old, new) = match free_args.as_slice() {
+        [old, new] => (PathBuf::from(old), PathBuf::from(new)),
+        _ => bail!("Expected OLD and NEW paths. Use '--help' for help on usage."),
+    };
+
+    Ok(Args {
+        show_help,
+        old,
+        new,
+        output_dir,
+        size,
+        zoom,
+    })
+}
+
+fn parse_path(s: &OsStr) -> Result<PathBuf, &'static str> {
+    Ok(s.into())
+}
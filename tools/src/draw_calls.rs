@@ -0,0 +1,190 @@
+use std::ffi::OsString;
+
+use anyhow::{anyhow, bail};
+use embedded_graphics::{
+    mono_font::{MonoFont, MonoTextStyle, MonoTextStyleBuilder},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use pico_args::Arguments;
+use profont::testing::{CountingDisplay, DrawCounts, RecordingDisplay};
+use profont::CellTextStyle;
+
+use crate::sizes::{self, SIZES};
+
+const HELP_MESSAGE: &str = "\
+profont draw-calls
+
+Reports the draw_iter, fill_contiguous and fill_solid calls a display receives
+when TEXT is drawn with each size and style, along with the pixels passed to
+them. On displays driven over SPI or I2C each call usually sets up a new window
+on the panel, so the number of calls matters as much as the number of pixels.
+
+The renderer is MonoTextStyle by default. 'cells' uses profont::CellTextStyle,
+which draws each character cell with one call, and 'lines' draws each line
+of text with one call.
+
+USAGE:
+    draw-calls [OPTIONS] [TEXT]
+
+FLAGS:
+  -h, --help                Prints help information
+
+OPTIONS:
+      --size      POINTS    Only report this size
+      --renderer  RENDERER  mono, cells or lines [default: mono]
+
+ARGS:
+  <TEXT>                    Text to draw [default: Hello world]
+";
+
+const DEFAULT_TEXT: &str = "Hello world";
+
+struct Args {
+    show_help: bool,
+    size: Option<u8>,
+    renderer: Renderer,
+    text: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+enum Renderer {
+    Mono,
+    Cells,
+    Lines,
+}
+
+pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
+    let args = parse_args(args)?;
+
+    if args.show_help {
+        println!("{}", HELP_MESSAGE);
+        return Ok(());
+    }
+
+    let text = args.text.as_deref().unwrap_or(DEFAULT_TEXT);
+    let selected = match args.size {
+        Some(point) => vec![sizes::find(point)?],
+        None => SIZES.iter().collect(),
+    };
+
+    println!("{:?}", text);
+    for size in selected {
+        let cell = size.font.character_size;
+        println!();
+        println!("{} ({}x{} cells)", size.name(), cell.width, cell.height);
+        println!(
+            "  {:<13}  {:>15}  {:>15}  {:>15}  {:>15}",
+            "style", "draw_iter", "fill_contiguous", "fill_solid", "total"
+        );
+        for (name, style) in styles(&size.font) {
+            let counts = draw(text, style, args.renderer);
+            println!(
+                "  {:<13}  {:>15}  {:>15}  {:>15}  {:>15}",
+                name,
+                column(counts.draw_iter_calls, counts.draw_iter_pixels),
+                column(counts.fill_contiguous_calls, counts.fill_contiguous_pixels),
+                column(counts.fill_solid_calls, counts.fill_solid_pixels),
+                column(counts.calls(), counts.pixels()),
+            );
+        }
+    }
+    println!();
+    println!("Each column shows calls / pixels.");
+
+    Ok(())
+}
+
+/// The styles to report, from plain text to every decoration with a background.
+fn styles<'a>(font: &'a MonoFont<'a>) -> Vec<(&'static str, MonoTextStyle<'a, BinaryColor>)> {
+    let base = MonoTextStyleBuilder::new()
+        .font(font)
+        .text_color(BinaryColor::On);
+    vec![
+        ("plain", base.build()),
+        (
+            "background",
+            base.background_color(BinaryColor::Off).build(),
+        ),
+        ("underline", base.underline().build()),
+        ("strikethrough", base.strikethrough().build()),
+        (
+            "all",
+            base.background_color(BinaryColor::Off)
+                .underline()
+                .strikethrough()
+                .build(),
+        ),
+    ]
+}
+
+fn draw(text: &str, style: MonoTextStyle<BinaryColor>, renderer: Renderer) -> DrawCounts {
+    let mut display = CountingDisplay::new(RecordingDisplay::new());
+    let position = Point::zero();
+    match renderer {
+        Renderer::Mono => Text::with_baseline(text, position, style, Baseline::Top)
+            .draw(&mut display)
+            .unwrap(),
+        Renderer::Cells => {
+            Text::with_baseline(text, position, CellTextStyle::new(style), Baseline::Top)
+                .draw(&mut display)
+                .unwrap()
+        }
+        Renderer::Lines => Text::with_baseline(
+            text,
+            position,
+            CellTextStyle::whole_lines(style),
+            Baseline::Top,
+        )
+        .draw(&mut display)
+        .unwrap(),
+    };
+    display.counts()
+}
+
+fn column(calls: usize, pixels: usize) -> String {
+    format!("{} / {}", calls, pixels)
+}
+
+fn parse_args(args: Vec<OsString>) -> Result<Args, anyhow::Error> {
+    let mut args = Arguments::from_vec(args);
+
+    let show_help = args.contains(["-h", "--help"]);
+    let size = args.opt_value_from_str("--size")?;
+    let renderer = args
+        .opt_value_from_fn("--renderer", parse_renderer)?
+        .unwrap_or(Renderer::Mono);
+
+    let mut free_args = args.finish();
+    let text = match free_args.len() {
+        0 => None,
+        1 => Some(
+            free_args
+                .pop()
+                .unwrap()
+                .into_string()
+                .map_err(|_| anyhow!("TEXT is not valid UTF-8"))?,
+        ),
+        _ => bail!("Unknown arguments. Use '--help' for help on usage."),
+    };
+
+    Ok(Args {
+        show_help,
+        size,
+        renderer,
+        text,
+    })
+}
+
+fn parse_renderer(s: &str) -> Result<Renderer, String> {
+    match s {
+        "mono" => Ok(Renderer::Mono),
+        "cells" => Ok(Renderer::Cells),
+        "lines" => Ok(Renderer::Lines),
+        _ => Err(format!(
+            "invalid renderer '{}', expected mono, cells or lines",
+            s
+        )),
+    }
+}
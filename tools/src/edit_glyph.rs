@@ -0,0 +1,571 @@
+use std::collections::BTreeSet;
+use std::ffi::{OsStr, OsString};
+#[cfg(feature = "sdl")]
+use std::fs;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "sdl")]
+use std::thread;
+#[cfg(feature = "sdl")]
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context};
+use embedded_graphics::{
+    image::ImageRaw,
+    mono_font::{mapping::GlyphMapping, MonoFont, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+};
+#[cfg(feature = "sdl")]
+use embedded_graphics_simulator::{
+    sdl2::{Keycode, Mod, MouseButton},
+    SimulatorEvent,
+};
+use embedded_graphics_simulator::{OutputSettingsBuilder, SimulatorDisplay, Window};
+use image::GrayImage;
+use pico_args::Arguments;
+use profont::{GLYPH_MAPPING, PROFONT_9_POINT};
+
+use crate::charset;
+use crate::generate;
+use crate::glyph_art::{self, Glyph};
+use crate::raw;
+use crate::render;
+use crate::sizes::{self, FontSize};
+
+const HELP_MESSAGE: &str = "\
+profont edit-glyph
+
+Edits the pixels of a glyph, previewing it in running text, and saves the
+result to the sprite sheet PNG and .raw file of the size, or to a text-art
+override file for 'generate --overrides'.
+
+USAGE:
+    edit-glyph [OPTIONS] CHAR
+
+CHAR is either the character itself or its code point written as U+XXXX.
+
+FLAGS:
+  -h, --help                Prints help information
+      --no-gui-scaling      Disable GUI scaling by factor 2
+
+OPTIONS:
+      --size        POINTS  ProFont size to edit [default: 12]
+      --sheets      DIR     Directory of the sprite sheet PNGs [default: tools/data]
+      --raw         DIR     Directory of the .raw files [default: font/data]
+      --overrides   FILE    Save edited glyphs to this text-art file instead of
+                            the sprite sheets. Glyphs already in the file are
+                            loaded as the starting point.
+      --zoom        N       Size of each glyph pixel [default: 8]
+
+MOUSE AND KEYS:
+  Click, drag               Toggle pixels
+  Left, Right               Edit the previous or next character
+  Ctrl+S                    Save
+  Escape                    Quit
+";
+const MARGIN: i32 = 8;
+/// How long to sleep between polls for input events.
+#[cfg(feature = "sdl")]
+const FRAME_TIME: Duration = Duration::from_millis(16);
+const BACKGROUND_COLOR: Rgb888 = Rgb888::BLACK;
+const TEXT_COLOR: Rgb888 = Rgb888::CSS_LIGHT_GRAY;
+const GRID_COLOR: Rgb888 = Rgb888::new(0x30, 0x30, 0x30);
+const CELL_COLOR: Rgb888 = Rgb888::new(0x18, 0x18, 0x18);
+const INK_COLOR: Rgb888 = Rgb888::WHITE;
+const BASELINE_COLOR: Rgb888 = Rgb888::RED;
+/// Lines of running text the glyph is previewed in. `{}` is replaced with the glyph.
+const PREVIEW_LINES: &[&str] = &[
+    "{}{}{}{}{}{}{}{}",
+    "n{}n o{}o H{}H O{}O",
+    "The quick {} brown fox",
+    "jumps over {} the lazy dog",
+];
+
+struct Args {
+    show_help: bool,
+    ch: char,
+    size: u8,
+    sheets: PathBuf,
+    raw: PathBuf,
+    overrides: Option<PathBuf>,
+    zoom: u32,
+    gui_scale: u32,
+}
+
+// Without SDL only the initial frame is drawn, so the editing state goes unused
+#[cfg_attr(not(feature = "sdl"), allow(dead_code))]
+struct Editor {
+    size: &'static FontSize,
+    sheet: GrayImage,
+    sheet_path: PathBuf,
+    raw_path: PathBuf,
+    overrides: Option<PathBuf>,
+    ch: char,
+    glyph: Glyph,
+    zoom: u32,
+    /// Characters changed since the editor was opened.
+    edited: BTreeSet<char>,
+    /// Whether there are changes that haven't been saved.
+    modified: bool,
+    /// The value being painted while the mouse button is held down.
+    painting: Option<bool>,
+}
+
+pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
+    let args = parse_args(args)?;
+
+    if args.show_help {
+        println!("{}", HELP_MESSAGE);
+        return Ok(());
+    }
+
+    let size = sizes::find(args.size)?;
+    let name = size.name();
+    let sheet_path = args.sheets.join(format!("{}.png", name));
+    let sheet = image::open(&sheet_path)
+        .with_context(|| format!("unable to read {}", sheet_path.display()))?
+        .into_luma8();
+    let cell = size.font.character_size;
+    if sheet.width() != generate::CHARS_PER_ROW as u32 * cell.width {
+        bail!(
+            "{} is {} pixels wide, expected {} for {}x{} cells",
+            sheet_path.display(),
+            sheet.width(),
+            generate::CHARS_PER_ROW as u32 * cell.width,
+            cell.width,
+            cell.height
+        );
+    }
+
+    let mut editor = Editor {
+        size,
+        sheet,
+        sheet_path,
+        raw_path: args.raw.join(format!("{}.raw", name)),
+        overrides: args.overrides,
+        ch: args.ch,
+        glyph: Glyph {
+            ch: args.ch,
+            width: 0,
+            height: 0,
+            pixels: Vec::new(),
+        },
+        zoom: args.zoom,
+        edited: BTreeSet::new(),
+        modified: false,
+        painting: None,
+    };
+    editor.load_overrides()?;
+    editor.select(args.ch)?;
+
+    let output_settings = OutputSettingsBuilder::new().scale(args.gui_scale).build();
+    let window = Window::new(
+        &format!("ProFont Glyph Editor - {}", name),
+        &output_settings,
+    );
+
+    run(editor, window)
+}
+
+#[cfg(feature = "sdl")]
+fn run(mut editor: Editor, mut window: Window) -> Result<(), anyhow::Error> {
+    let mut display = editor.draw();
+
+    loop {
+        window.update(&display);
+
+        let mut changed = false;
+        for event in window.events() {
+            match event {
+                SimulatorEvent::Quit
+                | SimulatorEvent::KeyDown {
+                    keycode: Keycode::Escape,
+                    ..
+                } => {
+                    if editor.modified {
+                        eprintln!("Warning: quit without saving changes");
+                    }
+                    return Ok(());
+                }
+                SimulatorEvent::KeyDown {
+                    keycode: Keycode::S,
+                    keymod,
+                    ..
+                } if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) => {
+                    editor.save()?;
+                    changed = true;
+                }
+                SimulatorEvent::KeyDown { keycode, .. } => {
+                    let next = match keycode {
+                        Keycode::Left => editor.neighbour(-1),
+                        Keycode::Right => editor.neighbour(1),
+                        _ => None,
+                    };
+                    if let Some(ch) = next {
+                        editor.select(ch)?;
+                        changed = true;
+                    }
+                }
+                SimulatorEvent::MouseButtonDown {
+                    mouse_btn: MouseButton::Left,
+                    point,
+                } => {
+                    if let Some((x, y)) = editor.pixel_at(point) {
+                        let value = !editor.glyph.pixel(x, y);
+                        editor.painting = Some(value);
+                        changed |= editor.set_pixel(x, y, value);
+                    }
+                }
+                SimulatorEvent::MouseMove { point } => {
+                    if let (Some(value), Some((x, y))) = (editor.painting, editor.pixel_at(point)) {
+                        changed |= editor.set_pixel(x, y, value);
+                    }
+                }
+                SimulatorEvent::MouseButtonUp {
+                    mouse_btn: MouseButton::Left,
+                    ..
+                } => editor.painting = None,
+                _ => {}
+            }
+        }
+
+        if changed {
+            display = editor.draw();
+        }
+        thread::sleep(FRAME_TIME);
+    }
+}
+
+/// Without SDL the editor can't take input, but the frame can still be captured with the
+/// `EG_SIMULATOR_DUMP` environment variable.
+#[cfg(not(feature = "sdl"))]
+fn run(editor: Editor, mut window: Window) -> Result<(), anyhow::Error> {
+    window.show_static(&editor.draw());
+    Ok(())
+}
+
+impl Editor {
+    /// Applies the glyphs in the override file, if it exists, to the sprite sheet.
+    fn load_overrides(&mut self) -> Result<(), anyhow::Error> {
+        let path = match self.overrides {
+            Some(ref path) if path.exists() => path.clone(),
+            _ => return Ok(()),
+        };
+
+        let cell = self.size.font.character_size;
+        for glyph in glyph_art::read(&path)? {
+            if !GLYPH_MAPPING.contains(glyph.ch) {
+                bail!(
+                    "{}: U+{:04X} is not in the font",
+                    path.display(),
+                    glyph.ch as u32
+                );
+            }
+            if (glyph.width, glyph.height) != (cell.width, cell.height) {
+                bail!(
+                    "{}: U+{:04X} is {}x{}, expected {}x{} for {}",
+                    path.display(),
+                    glyph.ch as u32,
+                    glyph.width,
+                    glyph.height,
+                    cell.width,
+                    cell.height,
+                    self.size.name()
+                );
+            }
+            let (x, y) = self.cell_origin(glyph.ch);
+            glyph.to_sheet(&mut self.sheet, x, y);
+            self.edited.insert(glyph.ch);
+        }
+
+        Ok(())
+    }
+
+    /// Switches to editing `ch`.
+    fn select(&mut self, ch: char) -> Result<(), anyhow::Error> {
+        if !GLYPH_MAPPING.contains(ch) {
+            bail!(
+                "U+{:04X} is not in the font, it would be drawn with the fallback glyph",
+                ch as u32
+            );
+        }
+        let cell = self.size.font.character_size;
+        let (x, y) = self.cell_origin(ch);
+        self.ch = ch;
+        self.glyph = Glyph::from_sheet(ch, &self.sheet, x, y, cell.width, cell.height);
+        Ok(())
+    }
+
+    /// Returns the character `offset` places away from the current one in the glyph mapping.
+    #[cfg(feature = "sdl")]
+    fn neighbour(&self, offset: isize) -> Option<char> {
+        let index = GLYPH_MAPPING.index(self.ch) as isize + offset;
+        if index < 0 {
+            return None;
+        }
+        GLYPH_MAPPING.chars().nth(index as usize)
+    }
+
+    fn cell_origin(&self, ch: char) -> (u32, u32) {
+        let cell = self.size.font.character_size;
+        generate::cell_origin(GLYPH_MAPPING.index(ch), cell.width, cell.height)
+    }
+
+    /// Sets a pixel of the glyph, returning `true` if it changed.
+    #[cfg(feature = "sdl")]
+    fn set_pixel(&mut self, x: u32, y: u32, value: bool) -> bool {
+        if self.glyph.pixel(x, y) == value {
+            return false;
+        }
+        self.glyph.pixels[(y * self.glyph.width + x) as usize] = value;
+        let (cell_x, cell_y) = self.cell_origin(self.ch);
+        self.glyph.to_sheet(&mut self.sheet, cell_x, cell_y);
+        self.edited.insert(self.ch);
+        self.modified = true;
+        true
+    }
+
+    /// Writes the sprite sheet and raw data, or the edited glyphs to the override file.
+    #[cfg(feature = "sdl")]
+    fn save(&mut self) -> Result<(), anyhow::Error> {
+        match self.overrides {
+            Some(ref path) => {
+                let cell = self.size.font.character_size;
+                let glyphs = self
+                    .edited
+                    .iter()
+                    .map(|&ch| {
+                        let (x, y) = self.cell_origin(ch);
+                        Glyph::from_sheet(ch, &self.sheet, x, y, cell.width, cell.height)
+                    })
+                    .collect::<Vec<_>>();
+                let header = format!(
+                    "{} glyph overrides, {}x{} pixel cells",
+                    self.size.name(),
+                    cell.width,
+                    cell.height
+                );
+                glyph_art::write(path, &header, &glyphs)?;
+                println!("Wrote {} glyph(s) to {}", glyphs.len(), path.display());
+            }
+            None => {
+                self.sheet
+                    .save(&self.sheet_path)
+                    .with_context(|| format!("unable to write {}", self.sheet_path.display()))?;
+                println!("Wrote {}", self.sheet_path.display());
+                write_raw(&self.raw_path, &self.sheet)?;
+                println!("Wrote {}", self.raw_path.display());
+            }
+        }
+        self.modified = false;
+        Ok(())
+    }
+
+    /// Returns the glyph pixel under a point of the display.
+    #[cfg(feature = "sdl")]
+    fn pixel_at(&self, point: Point) -> Option<(u32, u32)> {
+        let offset = point - self.grid_origin();
+        if offset.x < 0 || offset.y < 0 {
+            return None;
+        }
+        let (x, y) = (offset.x as u32 / self.zoom, offset.y as u32 / self.zoom);
+        if x < self.glyph.width && y < self.glyph.height {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+
+    fn grid_origin(&self) -> Point {
+        Point::new(
+            MARGIN,
+            MARGIN + PROFONT_9_POINT.character_size.height as i32 + 4,
+        )
+    }
+
+    /// Draws the enlarged glyph with the preview text beside it and the status below.
+    fn draw(&self) -> SimulatorDisplay<Rgb888> {
+        let font = &self.size.font;
+        let cell = font.character_size;
+        let text_style = MonoTextStyle::new(&PROFONT_9_POINT, TEXT_COLOR);
+        let line_height = PROFONT_9_POINT.character_size.height as i32;
+
+        // Preview with a font made from the edited sheet, so it draws exactly as the crate would
+        let raw = raw::pack(&self.sheet);
+        let preview_font = MonoFont {
+            image: ImageRaw::new(&raw, self.sheet.width()),
+            ..*font
+        };
+        let preview_style = MonoTextStyle::new(&preview_font, INK_COLOR);
+        let preview_lines = PREVIEW_LINES
+            .iter()
+            .map(|line| line.replace("{}", &self.ch.to_string()))
+            .collect::<Vec<_>>();
+        let preview_width = preview_lines
+            .iter()
+            .map(|line| render::text_width(font, line.chars().count()))
+            .max()
+            .unwrap_or(0) as i32;
+        let preview_line_height = cell.height as i32 + 2;
+
+        let title = format!(
+            "{} U+{:04X} '{}' glyph {}{}",
+            self.size.name(),
+            self.ch as u32,
+            self.ch,
+            GLYPH_MAPPING.index(self.ch),
+            if self.modified { " (modified)" } else { "" }
+        );
+        let destination = match self.overrides {
+            Some(ref path) => path.display().to_string(),
+            None => self.sheet_path.display().to_string(),
+        };
+        let status = [
+            "Click: toggle  Left/Right: character  Ctrl+S: save  Esc: quit".to_string(),
+            format!("Saves to {}", destination),
+        ];
+
+        let grid_origin = self.grid_origin();
+        let grid_size = Size::new(cell.width * self.zoom + 1, cell.height * self.zoom + 1);
+        let preview_origin = Point::new(
+            grid_origin.x + grid_size.width as i32 + MARGIN * 2,
+            grid_origin.y,
+        );
+        let content_height =
+            (grid_size.height as i32).max(preview_line_height * preview_lines.len() as i32);
+        let status_top = grid_origin.y + content_height + MARGIN;
+        let status_width = status
+            .iter()
+            .chain(Some(&title))
+            .map(|line| render::text_width(&PROFONT_9_POINT, line.chars().count()))
+            .max()
+            .unwrap_or(0) as i32;
+        let width = (preview_origin.x + preview_width).max(MARGIN + status_width) + MARGIN;
+        let height = status_top + line_height * status.len() as i32 + MARGIN;
+
+        let mut display = SimulatorDisplay::<Rgb888>::new(Size::new(width as u32, height as u32));
+        display.clear(BACKGROUND_COLOR).unwrap();
+
+        Text::with_baseline(
+            &title,
+            Point::new(MARGIN, MARGIN),
+            text_style,
+            Baseline::Top,
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        // The grid lines are the gaps between the pixels
+        Rectangle::new(grid_origin, grid_size)
+            .into_styled(PrimitiveStyle::with_fill(GRID_COLOR))
+            .draw(&mut display)
+            .unwrap();
+        for y in 0..self.glyph.height {
+            for x in 0..self.glyph.width {
+                let color = if self.glyph.pixel(x, y) {
+                    INK_COLOR
+                } else {
+                    CELL_COLOR
+                };
+                let top_left = grid_origin
+                    + Point::new((x * self.zoom) as i32, (y * self.zoom) as i32)
+                    + Point::new(1, 1);
+                Rectangle::new(top_left, Size::new_equal(self.zoom - 1))
+                    .into_styled(PrimitiveStyle::with_fill(color))
+                    .draw(&mut display)
+                    .unwrap();
+            }
+        }
+        // The baseline runs along the bottom of the baseline row
+        Rectangle::new(
+            grid_origin + Point::new(0, ((font.baseline + 1) * self.zoom) as i32),
+            Size::new(grid_size.width, 1),
+        )
+        .into_styled(PrimitiveStyle::with_fill(BASELINE_COLOR))
+        .draw(&mut display)
+        .unwrap();
+
+        let mut position = preview_origin;
+        for line in &preview_lines {
+            Text::with_baseline(line, position, preview_style, Baseline::Top)
+                .draw(&mut display)
+                .unwrap();
+            position.y += preview_line_height;
+        }
+
+        let mut position = Point::new(MARGIN, status_top);
+        for line in &status {
+            Text::with_baseline(line, position, text_style, Baseline::Top)
+                .draw(&mut display)
+                .unwrap();
+            position.y += line_height;
+        }
+
+        display
+    }
+}
+
+#[cfg(feature = "sdl")]
+fn write_raw(path: &Path, sheet: &GrayImage) -> Result<(), anyhow::Error> {
+    fs::write(path, raw::pack(sheet)).with_context(|| format!("unable to write {}", path.display()))
+}
+
+fn parse_args(args: Vec<OsString>) -> Result<Args, anyhow::Error> {
+    let mut args = Arguments::from_vec(args);
+
+    let show_help = args.contains(["-h", "--help"]);
+    if show_help {
+        return Ok(Args {
+            show_help,
+            ch: ' ',
+            size: 0,
+            sheets: PathBuf::new(),
+            raw: PathBuf::new(),
+            overrides: None,
+            zoom: 0,
+            gui_scale: 1,
+        });
+    }
+
+    let no_gui_scaling = args.contains("--no-gui-scaling");
+    let size = args.opt_value_from_str("--size")?.unwrap_or(12);
+    let sheets = args
+        .opt_value_from_os_str("--sheets", parse_path)?
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("data"));
+    let raw = args
+        .opt_value_from_os_str("--raw", parse_path)?
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../font/data"));
+    let overrides = args.opt_value_from_os_str("--overrides", parse_path)?;
+    let zoom = args.opt_value_from_str("--zoom")?.unwrap_or(8);
+    if zoom < 2 {
+        bail!("--zoom must be at least 2 to leave room for the pixel grid");
+    }
+
+    let mut free_args = args.finish();
+    if free_args.len() != 1 {
+        bail!("Expected a single CHAR. Use '--help' for help on usage.");
+    }
+    let ch = free_args
+        .pop()
+        .unwrap()
+        .into_string()
+        .map_err(|_| anyhow!("CHAR is not valid UTF-8"))?;
+    let ch = charset::parse_char(&ch).map_err(|err| anyhow!(err))?;
+
+    Ok(Args {
+        show_help,
+        ch,
+        size,
+        sheets,
+        raw,
+        overrides,
+        zoom,
+        gui_scale: if no_gui_scaling { 1 } else { 2 },
+    })
+}
+
+fn parse_path(s: &OsStr) -> Result<PathBuf, &'static str> {
+    Ok(s.into())
+}
@@ -0,0 +1,383 @@
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+#[cfg(feature = "sdl")]
+use std::thread;
+#[cfg(feature = "sdl")]
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use embedded_graphics::{
+    mono_font::MonoTextStyle,
+    pixelcolor::Rgb888,
+    prelude::*,
+    text::{Baseline, Text},
+};
+#[cfg(feature = "sdl")]
+use embedded_graphics_simulator::{sdl2::Keycode, SimulatorEvent};
+use embedded_graphics_simulator::{
+    OutputSettings, OutputSettingsBuilder, SimulatorDisplay, Window,
+};
+use pico_args::Arguments;
+
+use crate::profiles::{self, ColorModel, DisplayProfile};
+use crate::render;
+use crate::sizes::{self, FontSize, SIZES};
+
+const HELP_MESSAGE: &str = "\
+profont epaper
+
+Simulates an e-paper panel showing sample text in each ProFont size, including
+the ghosting left by partial refreshes and the flashing of full refreshes.
+
+USAGE:
+    epaper [OPTIONS]
+
+FLAGS:
+  -h, --help                Prints help information
+      --ghosting            Show the residue of the previous page after a
+                            partial refresh
+      --flash               Show the frames of each full refresh
+      --no-gui-scaling      Disable GUI scaling by factor 2
+
+OPTIONS:
+      --profile     NAME    Display profile to simulate [default: inky-phat]
+      --size        POINTS  Size to show first [default: 12]
+  -o, --output      FILE    Write a PNG of the page instead of opening a window.
+                            With --ghosting the page is shown after a partial
+                            refresh from the previous size. With --flash the
+                            frames of the full refresh are written to
+                            FILE-flash-N.png.
+
+KEYS:
+  Right, Space              Partial refresh to the next size
+  Left                      Partial refresh to the previous size
+  F                         Full refresh
+  Escape                    Quit
+";
+const SAMPLE_TEXT: &str = "The quick brown fox jumps over the lazy dog. \
+                           0123456789 (){}[] <=> +-*/ !?&@#$%\n\
+                           ÀÉÎÕÜ àéîõü ßæøåç ¡¿«» °±µ¶§";
+/// How long each frame of a full refresh is shown for.
+#[cfg(feature = "sdl")]
+const FLASH_FRAME_TIME: Duration = Duration::from_millis(300);
+/// How long to sleep between polls for input events.
+#[cfg(feature = "sdl")]
+const FRAME_TIME: Duration = Duration::from_millis(16);
+
+/// The colour of an e-paper pixel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpaperColor {
+    White,
+    Black,
+    Red,
+}
+
+impl PixelColor for EpaperColor {
+    type Raw = ();
+}
+
+impl From<EpaperColor> for Rgb888 {
+    fn from(color: EpaperColor) -> Self {
+        match color {
+            EpaperColor::White => Rgb888::new(0xf2, 0xf0, 0xe8),
+            EpaperColor::Black => Rgb888::new(0x20, 0x20, 0x20),
+            EpaperColor::Red => Rgb888::new(0xc0, 0x20, 0x20),
+        }
+    }
+}
+
+struct Args {
+    show_help: bool,
+    profile: &'static DisplayProfile,
+    size: &'static FontSize,
+    ghosting: bool,
+    flash: bool,
+    output: Option<PathBuf>,
+    gui_scale: u32,
+}
+
+/// The state of the pixels on the panel.
+struct Panel {
+    size: Size,
+    pixels: Vec<EpaperColor>,
+    /// Residue of the colour each pixel had before the last partial refresh that changed it.
+    ghosts: Vec<Option<EpaperColor>>,
+}
+
+impl Panel {
+    fn new(size: Size) -> Self {
+        let len = (size.width * size.height) as usize;
+        Panel {
+            size,
+            pixels: vec![EpaperColor::White; len],
+            ghosts: vec![None; len],
+        }
+    }
+
+    /// Updates only the pixels that changed, which leaves a faint image of their old colour
+    /// behind.
+    fn partial_refresh(&mut self, frame: &SimulatorDisplay<EpaperColor>, ghosting: bool) {
+        for (i, point) in frame.bounding_box().points().enumerate() {
+            let color = frame.get_pixel(point);
+            if color != self.pixels[i] {
+                if ghosting {
+                    self.ghosts[i] = Some(self.pixels[i]);
+                }
+                self.pixels[i] = color;
+            }
+        }
+    }
+
+    /// Drives every pixel through black and white before showing the frame, which clears any
+    /// ghosting. Returns the frames shown along the way.
+    fn full_refresh(
+        &mut self,
+        frame: &SimulatorDisplay<EpaperColor>,
+    ) -> Vec<SimulatorDisplay<Rgb888>> {
+        let inverse = |color| match color {
+            EpaperColor::White => EpaperColor::Black,
+            _ => EpaperColor::White,
+        };
+        type Fill = fn(EpaperColor) -> EpaperColor;
+        let steps: [Option<Fill>; 4] = [
+            Some(inverse),
+            Some(|_| EpaperColor::Black),
+            Some(|_| EpaperColor::White),
+            None,
+        ];
+        let mut frames = Vec::new();
+        for fill in steps.iter() {
+            for (i, point) in frame.bounding_box().points().enumerate() {
+                self.pixels[i] = match fill {
+                    Some(fill) => fill(self.pixels[i]),
+                    None => frame.get_pixel(point),
+                };
+                self.ghosts[i] = None;
+            }
+            frames.push(self.to_display());
+        }
+        frames
+    }
+
+    /// Returns what the panel looks like, with ghosts blended into the pixels they linger on.
+    fn to_display(&self) -> SimulatorDisplay<Rgb888> {
+        let mut display = SimulatorDisplay::new(self.size);
+        let pixels = display
+            .bounding_box()
+            .points()
+            .zip(self.pixels.iter().zip(&self.ghosts))
+            .map(|(point, (&color, &ghost))| {
+                let color = match ghost {
+                    Some(ghost) => blend(color.into(), ghost.into()),
+                    None => color.into(),
+                };
+                Pixel(point, color)
+            });
+        display.draw_iter(pixels).unwrap();
+        display
+    }
+}
+
+/// Mixes a fifth of the ghost colour into a pixel.
+fn blend(color: Rgb888, ghost: Rgb888) -> Rgb888 {
+    let mix = |a: u8, b: u8| ((u16::from(a) * 4 + u16::from(b)) / 5) as u8;
+    Rgb888::new(
+        mix(color.r(), ghost.r()),
+        mix(color.g(), ghost.g()),
+        mix(color.b(), ghost.b()),
+    )
+}
+
+/// Draws a page of sample text in one size, with a heading in red if the panel has red ink.
+fn draw_page(profile: &DisplayProfile, size: &FontSize) -> SimulatorDisplay<EpaperColor> {
+    let font = &size.font;
+    let has_red = matches!(profile.color_model, ColorModel::BlackWhiteRed);
+    let heading_color = if has_red {
+        EpaperColor::Red
+    } else {
+        EpaperColor::Black
+    };
+    let (columns, rows) = profile.text_grid(font);
+    let heading = format!("ProFont {}pt, {}x{}", size.point, columns, rows);
+
+    let mut display = SimulatorDisplay::with_default_color(profile.size, EpaperColor::White);
+    let mut position = Point::zero();
+    let lines = render::wrap(&heading, Some(columns))
+        .into_iter()
+        .map(|line| (line, heading_color))
+        .chain(
+            render::wrap(SAMPLE_TEXT, Some(columns))
+                .into_iter()
+                .map(|line| (line, EpaperColor::Black)),
+        );
+    for (line, color) in lines {
+        Text::with_baseline(
+            &line,
+            position,
+            MonoTextStyle::new(font, color),
+            Baseline::Top,
+        )
+        .draw(&mut display)
+        .unwrap();
+        position.y += font.character_size.height as i32;
+    }
+    display
+}
+
+pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
+    let args = parse_args(args)?;
+
+    if args.show_help {
+        println!("{}", HELP_MESSAGE);
+        return Ok(());
+    }
+
+    let mut panel = Panel::new(args.profile.size);
+    let index = SIZES
+        .iter()
+        .position(|size| size.point == args.size.point)
+        .unwrap();
+
+    if let Some(ref path) = args.output {
+        // Start from the previous size so a partial refresh has something to leave behind
+        let first = if args.ghosting {
+            (index + SIZES.len() - 1) % SIZES.len()
+        } else {
+            index
+        };
+        let flash_frames = panel.full_refresh(&draw_page(args.profile, &SIZES[first]));
+        if args.ghosting {
+            panel.partial_refresh(&draw_page(args.profile, args.size), true);
+        }
+        if args.flash {
+            for (i, frame) in flash_frames.iter().enumerate() {
+                let path = flash_frame_path(path, i + 1);
+                save_png(frame, &path)?;
+            }
+        }
+        return save_png(&panel.to_display(), path);
+    }
+
+    // The panel is drawn in its actual colours, so the profile's theme isn't used
+    let output_settings = OutputSettingsBuilder::new().scale(args.gui_scale).build();
+    let title = format!("ProFont e-paper - {}", args.profile.description);
+    let window = Window::new(&title, &output_settings);
+
+    run(&args, panel, index, window)
+}
+
+#[cfg(feature = "sdl")]
+fn run(
+    args: &Args,
+    mut panel: Panel,
+    mut index: usize,
+    mut window: Window,
+) -> Result<(), anyhow::Error> {
+    let mut full_refresh = true;
+    let mut display = panel.to_display();
+
+    loop {
+        if full_refresh {
+            let frames = panel.full_refresh(&draw_page(args.profile, &SIZES[index]));
+            if args.flash {
+                for frame in &frames[..frames.len() - 1] {
+                    window.update(frame);
+                    thread::sleep(FLASH_FRAME_TIME);
+                }
+            }
+            display = panel.to_display();
+            full_refresh = false;
+        }
+        window.update(&display);
+
+        for event in window.events() {
+            match event {
+                SimulatorEvent::Quit
+                | SimulatorEvent::KeyDown {
+                    keycode: Keycode::Escape,
+                    ..
+                } => return Ok(()),
+                SimulatorEvent::KeyDown { keycode, .. } => {
+                    let next = match keycode {
+                        Keycode::Right | Keycode::Space => (index + 1) % SIZES.len(),
+                        Keycode::Left => (index + SIZES.len() - 1) % SIZES.len(),
+                        Keycode::F => {
+                            full_refresh = true;
+                            continue;
+                        }
+                        _ => continue,
+                    };
+                    index = next;
+                    panel.partial_refresh(&draw_page(args.profile, &SIZES[index]), args.ghosting);
+                    display = panel.to_display();
+                }
+                _ => {}
+            }
+        }
+        thread::sleep(FRAME_TIME);
+    }
+}
+
+/// Without SDL the panel can't be refreshed, but the first page can still be captured with the
+/// `EG_SIMULATOR_DUMP` environment variable.
+#[cfg(not(feature = "sdl"))]
+fn run(
+    args: &Args,
+    mut panel: Panel,
+    index: usize,
+    mut window: Window,
+) -> Result<(), anyhow::Error> {
+    panel.full_refresh(&draw_page(args.profile, &SIZES[index]));
+    window.show_static(&panel.to_display());
+    Ok(())
+}
+
+/// Returns `FILE-flash-N.png` for an output path of `FILE.png`.
+fn flash_frame_path(path: &Path, frame: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}-flash-{}.png", stem, frame))
+}
+
+fn save_png(display: &SimulatorDisplay<Rgb888>, path: &Path) -> Result<(), anyhow::Error> {
+    display
+        .to_rgb_output_image(&OutputSettings::default())
+        .save_png(path)?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+fn parse_args(args: Vec<OsString>) -> Result<Args, anyhow::Error> {
+    let mut args = Arguments::from_vec(args);
+
+    let show_help = args.contains(["-h", "--help"]);
+    let ghosting = args.contains("--ghosting");
+    let flash = args.contains("--flash");
+    let no_gui_scaling = args.contains("--no-gui-scaling");
+    let profile = args
+        .opt_value_from_fn("--profile", profiles::find)?
+        .map_or_else(|| profiles::find("inky-phat"), Ok)
+        .map_err(|err| anyhow!(err))?;
+    let size = sizes::find(args.opt_value_from_str("--size")?.unwrap_or(12))?;
+    let output = args.opt_value_from_os_str(["-o", "--output"], parse_path)?;
+
+    if !args.finish().is_empty() {
+        bail!("Unknown arguments. Use '--help' for help on usage.");
+    }
+    if flash && output.is_none() && !cfg!(feature = "sdl") {
+        bail!("--flash needs a window with SDL, or --output");
+    }
+
+    Ok(Args {
+        show_help,
+        profile,
+        size,
+        ghosting,
+        flash,
+        output,
+        gui_scale: if no_gui_scaling { 1 } else { 2 },
+    })
+}
+
+fn parse_path(s: &OsStr) -> Result<PathBuf, &'static str> {
+    Ok(s.into())
+}
@@ -1,18 +1,22 @@
-use std::char;
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::PathBuf;
 
 use allsorts::binary::read::ReadScope;
-use allsorts::bitmap::cbdt::CBLCTable;
+use allsorts::bitmap::cbdt::{BitmapInfo, CBLCTable};
 use allsorts::bitmap::{BitDepth, Bitmap, BitmapGlyph, BitmapMetrics, Metrics};
 use allsorts::font::{GlyphTableFlags, MatchingPresentation};
 use allsorts::tables::FontTableProvider;
-use allsorts::tables::OpenTypeFont;
+use allsorts::tables::{OffsetTableFontProvider, OpenTypeFont};
 use allsorts::{tag, Font};
 use anyhow::{anyhow, bail};
-use image::{GenericImage, GrayImage};
+use image::{GrayImage, Luma};
 use pico_args::Arguments;
 
-const CHARS_PER_ROW: i32 = 32;
+use crate::charset::Charset;
+use crate::glyph_art::{self, Glyph};
+
+pub const CHARS_PER_ROW: i32 = 32;
 const HELP_MESSAGE: &str = "\
 profont generate
 
@@ -26,18 +30,102 @@ FLAGS:
 
 OPTIONS:
       --strike    SIZE  Bitmap size within the font to extract
-      --font_size SIZE  Output size to write the strike as
+      --font-size SIZE  Output size to write the strike as
+      --charset   FILE  Characters to include, as plain text or U+XXXX..U+YYYY
+                        ranges, one or more per line [default: Latin-1]
+      --fallback  CHAR  Character to show for unmapped characters [default: ?]
+      --output    FILE  Path of the PNG to write [default: ProFont<SIZE>Point.png]
+      --overrides FILE  Text-art glyphs to use in place of the ones in the font
+      --export-glyphs FILE
+                        Write the generated glyphs to FILE in text-art form
+      --advance   PX    Override the advance (cell width) of the strike
+      --ascender  PX    Override the ascender of the strike
+      --descender PX    Override the descender of the strike
+      --offset-x  PX    Shift all glyphs right within their cell
+      --offset-y  PX    Shift all glyphs up within their cell
+      --clip            Clip glyphs that extend outside their cell instead of
+                        failing
+
+When --charset is given the matching glyph mapping is written next to the PNG
+with a .rs extension.
 ";
 
+/// Adjustments to the metrics of a strike.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MetricOverrides {
+    advance: Option<u8>,
+    ascender: Option<i8>,
+    descender: Option<i8>,
+    /// Added to the horizontal origin offset of every glyph.
+    offset_x: Option<i16>,
+    /// Added to the vertical origin offset of every glyph.
+    offset_y: Option<i16>,
+}
+
+const NO_OVERRIDES: MetricOverrides = MetricOverrides {
+    advance: None,
+    ascender: None,
+    descender: None,
+    offset_x: None,
+    offset_y: None,
+};
+
+/// Metric overrides applied to strikes by default, keyed by strike size.
+const STRIKE_OVERRIDES: &[(u8, MetricOverrides)] = &[
+    // The 16px font has an incorrect advance of 14, which spreads the characters out too much
+    (
+        16,
+        MetricOverrides {
+            advance: Some(10),
+            ..NO_OVERRIDES
+        },
+    ),
+];
+
+impl MetricOverrides {
+    /// Returns the default overrides for `strike`, replaced by any set in `self`.
+    fn for_strike(&self, strike: u8) -> MetricOverrides {
+        let defaults = STRIKE_OVERRIDES
+            .iter()
+            .find(|(size, _)| *size == strike)
+            .map_or(NO_OVERRIDES, |&(_, overrides)| overrides);
+
+        MetricOverrides {
+            advance: self.advance.or(defaults.advance),
+            ascender: self.ascender.or(defaults.ascender),
+            descender: self.descender.or(defaults.descender),
+            offset_x: self.offset_x.or(defaults.offset_x),
+            offset_y: self.offset_y.or(defaults.offset_y),
+        }
+    }
+
+    fn apply(&self, metrics: &mut BitmapMetrics) {
+        if let Some(advance) = self.advance {
+            metrics.advance = advance;
+        }
+        if let Some(ascender) = self.ascender {
+            metrics.ascender = ascender;
+        }
+        if let Some(descender) = self.descender {
+            metrics.descender = descender;
+        }
+    }
+}
+
 struct Args {
     show_help: bool,
     strike: u8,
     font_size: u8,
+    charset: Option<PathBuf>,
+    fallback: char,
+    output: Option<PathBuf>,
+    overrides: Option<PathBuf>,
+    export_glyphs: Option<PathBuf>,
+    metric_overrides: MetricOverrides,
+    clip: bool,
 }
 
 pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
-    // Read font
-    let font_data = include_bytes!("../data/profontn.otb");
     let args = parse_args(args)?;
 
     if args.show_help {
@@ -45,6 +133,172 @@ pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
         return Ok(());
     }
 
+    let (mut font, bitmap_infos) = load_font()?;
+    let mut metrics = space_metrics(&mut font, &bitmap_infos, args.strike)?;
+    let metric_overrides = args.metric_overrides.for_strike(args.strike);
+    metric_overrides.apply(&mut metrics);
+    let (cell_width, cell_height) = cell_size(&metrics)?;
+    // dbg!(metrics);
+
+    // Look up the glyphs. Characters the strike has no image for can only be left out of a custom
+    // charset, which gets a glyph mapping to match; the built-in mapping expects all of Latin-1.
+    let charset = match args.charset {
+        Some(ref path) => Charset::from_file(path)?,
+        None => Charset::latin1(),
+    };
+    let mut glyph_images = Vec::with_capacity(charset.len());
+    let mut missing = Vec::new();
+    for &ch in charset.chars() {
+        let (glyph_index, _vs) =
+            font.lookup_glyph_index(ch, MatchingPresentation::NotRequired, None);
+        if glyph_index == 0 {
+            missing.push(ch);
+            continue;
+        }
+
+        match font.lookup_glyph_image(glyph_index, u16::from(args.strike), BitDepth::One)? {
+            Some(image) => glyph_images.push((ch, image)),
+            None => missing.push(ch),
+        }
+    }
+    if !missing.is_empty() {
+        if args.charset.is_none() {
+            bail!(
+                "No glyph for {} character(s): {}",
+                missing.len(),
+                format_chars(&missing)
+            );
+        }
+        eprintln!(
+            "Warning: no glyph for {} character(s), leaving them out: {}",
+            missing.len(),
+            format_chars(&missing)
+        );
+    }
+    if glyph_images.is_empty() {
+        bail!("None of the characters in the charset have glyphs");
+    }
+    let charset = Charset::from_chars(glyph_images.iter().map(|&(ch, _)| ch));
+
+    let fallback_index = match charset.index(args.fallback) {
+        Some(index) => index,
+        None => {
+            eprintln!(
+                "Warning: fallback character '{}' is not in the charset, using '{}' instead",
+                args.fallback,
+                charset.chars()[0]
+            );
+            0
+        }
+    };
+
+    // Render the glyphs
+    let img_width = cell_width * CHARS_PER_ROW as u32;
+    let rows = (charset.len() as u32 + CHARS_PER_ROW as u32 - 1) / CHARS_PER_ROW as u32;
+    let img_height = cell_height * rows;
+    let mut imgbuf = GrayImage::new(img_width, img_height);
+    let mut out_of_bounds = Vec::new();
+    for (i, &(ch, ref glyph_image)) in glyph_images.iter().enumerate() {
+        // eprintln!("Got bitmap for '{}': {:?}", ch, glyph_image.metrics);
+        if !add_to_sprite_sheet(i, &metrics, &metric_overrides, glyph_image, &mut imgbuf)? {
+            out_of_bounds.push(ch);
+        }
+    }
+
+    check_out_of_bounds(&out_of_bounds, cell_width, cell_height, args.clip)?;
+
+    // Apply hand edited glyphs on top of the ones from the font
+    if let Some(ref path) = args.overrides {
+        let overrides = glyph_art::read(path)?;
+        for glyph in &overrides {
+            let index = charset.index(glyph.ch).ok_or_else(|| {
+                anyhow!(
+                    "{}: U+{:04X} is not in the charset",
+                    path.display(),
+                    glyph.ch as u32
+                )
+            })?;
+            if (glyph.width, glyph.height) != (cell_width, cell_height) {
+                bail!(
+                    "{}: U+{:04X} is {}x{}, expected {}x{}",
+                    path.display(),
+                    glyph.ch as u32,
+                    glyph.width,
+                    glyph.height,
+                    cell_width,
+                    cell_height
+                );
+            }
+            let (x, y) = cell_origin(index, cell_width, cell_height);
+            glyph.to_sheet(&mut imgbuf, x, y);
+        }
+        println!(
+            "Applied {} glyph override(s) from {}",
+            overrides.len(),
+            path.display()
+        );
+    }
+
+    // Write out the PNG
+    let filename = args
+        .output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("ProFont{}Point.png", args.font_size)));
+    imgbuf.save(&filename)?;
+    println!(
+        "Wrote {} with character size of {}x{}",
+        filename.display(),
+        cell_width,
+        cell_height
+    );
+
+    // Write out the glyph mapping to go with a custom charset
+    if args.charset.is_some() {
+        let mapping_filename = filename.with_extension("rs");
+        let mapping = format!(
+            "\
+// Glyph mapping for {} ({} characters). Unknown characters fall back to {:?}.
+const GLYPH_MAPPING: StrGlyphMapping =
+    StrGlyphMapping::new({:?}, {});
+",
+            filename.display(),
+            charset.len(),
+            charset.chars()[fallback_index],
+            charset.glyph_mapping(),
+            fallback_index
+        );
+        fs::write(&mapping_filename, mapping)?;
+        println!("Wrote {}", mapping_filename.display());
+    }
+
+    // Write out the glyphs as text-art
+    if let Some(ref path) = args.export_glyphs {
+        let glyphs = charset
+            .chars()
+            .iter()
+            .enumerate()
+            .map(|(i, &ch)| {
+                let (x, y) = cell_origin(i, cell_width, cell_height);
+                Glyph::from_sheet(ch, &imgbuf, x, y, cell_width, cell_height)
+            })
+            .collect::<Vec<_>>();
+        let header = format!(
+            "ProFont{}Point glyphs from the {}px strike, {}x{} pixel cells",
+            args.font_size, args.strike, cell_width, cell_height
+        );
+        glyph_art::write(path, &header, &glyphs)?;
+        println!("Wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+type SourceFont = Font<OffsetTableFontProvider<'static>>;
+
+/// Reads the source font and the list of bitmap strikes it contains.
+fn load_font() -> Result<(SourceFont, Vec<BitmapInfo>), anyhow::Error> {
+    // Read font
+    let font_data = include_bytes!("../data/profontn.otb");
     let scope = ReadScope::new(font_data);
     let font_file = scope.read::<OpenTypeFont>()?;
     let table_provider = font_file.table_provider(0)?;
@@ -71,99 +325,101 @@ pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
         .ok_or_else(|| anyhow!("Unable to find suitable cmap table for character mapping"))?;
     font.set_embedded_image_filter(GlyphTableFlags::EBDT);
 
-    // Print latin 1 characters
-    let basic = (' ' as u32..='~' as u32)
-        .into_iter()
-        .map(|c| char::from_u32(c).unwrap());
-    let extended = ('\u{00A0}' as u32..='ÿ' as u32)
-        .into_iter()
-        .map(|c| char::from_u32(c).unwrap());
-    let all_chars = basic.chain(extended).collect::<Vec<_>>(); // TODO: Do we really need to collect these?
+    Ok((font, bitmap_infos))
+}
+
+/// Determines the glyph metrics of a strike by looking up the space character.
+fn space_metrics(
+    font: &mut SourceFont,
+    bitmap_infos: &[BitmapInfo],
+    strike: u8,
+) -> Result<BitmapMetrics, anyhow::Error> {
+    if !bitmap_infos.iter().any(|info| info.ppem_x == strike) {
+        let sizes: String = bitmap_infos
+            .iter()
+            .map(|info| info.ppem_x.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        bail!(
+            "Unable to find strike with size {}. Available: {}",
+            strike,
+            sizes
+        );
+    }
 
-    let strike = bitmap_infos
-        .iter()
-        .find(|strike| strike.ppem_x == args.strike)
-        .ok_or_else(|| {
-            let sizes: String = bitmap_infos
-                .iter()
-                .map(|info| info.ppem_x.to_string())
-                .collect::<Vec<_>>()
-                .join(", ");
-            anyhow!(
-                "Unable to find strike with size {}. Available: {}",
-                args.strike,
-                sizes
-            )
-        })?;
-
-    // Determine the glyph metrics by looking up the space character
     let (space_index, _vs) = font.lookup_glyph_index(' ', MatchingPresentation::NotRequired, None);
     let space_image =
-        match font.lookup_glyph_image(space_index, u16::from(strike.ppem_x), BitDepth::One)? {
+        match font.lookup_glyph_image(space_index, u16::from(strike), BitDepth::One)? {
             Some(image) => image,
             None => {
                 bail!("No image for the space character found");
             }
         };
 
-    let mut metrics = *match space_image.metrics {
+    let metrics = *match space_image.metrics {
         Metrics::Embedded(ref metrics) => metrics.hori(),
         Metrics::HmtxVmtx(_) => {
             bail!("expected embedded metrics, got hmtx/vmtx metrics")
         }
     }
     .ok_or_else(|| anyhow!("missing horizontal metrics"))?;
-    if args.strike == 16 {
-        // The 16px font has an incorrect advance of 14, which spreads the characters out
-        // too much
-        metrics.advance = 10;
-    }
-    // dbg!(metrics);
 
-    // Render the glyphs
-    let img_width = (i32::from(metrics.advance) * CHARS_PER_ROW) as u32;
-    let img_height = ((metrics.ascender - metrics.descender) as f64 * all_chars.len() as f64
-        / CHARS_PER_ROW as f64)
-        .ceil() as u32;
-    let mut imgbuf = GrayImage::new(img_width, img_height);
-    for (i, &ch) in all_chars.iter().enumerate() {
-        let (glyph_index, _vs) =
-            font.lookup_glyph_index(ch, MatchingPresentation::NotRequired, None);
-        if glyph_index == 0 {
-            bail!("No glyph for '{}'", ch);
-        }
+    Ok(metrics)
+}
 
-        let glyph_image =
-            match font.lookup_glyph_image(glyph_index, u16::from(strike.ppem_x), BitDepth::One)? {
-                Some(image) => image,
-                None => {
-                    bail!("No image for '{}' ({})", ch, ch as u32);
-                }
-            };
+/// Returns the metrics of a strike in the source font as used by `generate` by default.
+pub fn strike_metrics(strike: u8) -> Result<BitmapMetrics, anyhow::Error> {
+    let (mut font, bitmap_infos) = load_font()?;
+    let mut metrics = space_metrics(&mut font, &bitmap_infos, strike)?;
+    NO_OVERRIDES.for_strike(strike).apply(&mut metrics);
+    Ok(metrics)
+}
 
-        // eprintln!("Got bitmap for '{}': {:?}", ch, glyph_image.metrics);
-        add_to_sprite_sheet(i, &metrics, &glyph_image, &mut imgbuf)?;
+/// Returns the width and height of the character cell of a strike with `metrics`.
+///
+/// The metrics can come from the command line, so they're checked rather than trusted.
+fn cell_size(metrics: &BitmapMetrics) -> Result<(u32, u32), anyhow::Error> {
+    if metrics.advance == 0 {
+        bail!("advance must be at least 1");
+    }
+    // Widen before subtracting, as the difference of two i8s can overflow
+    let height = i32::from(metrics.ascender) - i32::from(metrics.descender);
+    if height <= 0 {
+        bail!(
+            "ascender ({}) must be greater than descender ({})",
+            metrics.ascender,
+            metrics.descender
+        );
     }
+    Ok((u32::from(metrics.advance), height as u32))
+}
 
-    // Write out the PNG
-    let filename = format!("ProFont{}Point.png", args.font_size);
-    imgbuf.save(&filename)?;
-    println!(
-        "Wrote {} with character size of {}x{}",
-        filename,
-        metrics.advance,
-        metrics.ascender - metrics.descender
-    );
+/// Returns the top left corner of a character's cell in the sprite sheet.
+pub fn cell_origin(char_index: usize, cell_width: u32, cell_height: u32) -> (u32, u32) {
+    let col = char_index as u32 % CHARS_PER_ROW as u32;
+    let row = char_index as u32 / CHARS_PER_ROW as u32;
+    (col * cell_width, row * cell_height)
+}
 
-    Ok(())
+fn format_chars(chars: &[char]) -> String {
+    chars
+        .iter()
+        .map(|&ch| format!("U+{:04X} '{}'", ch as u32, ch))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
+/// Copies a glyph into its cell in the sprite sheet.
+///
+/// Any pixels that fall outside the cell are left out. Returns `false` if there were set pixels
+/// outside the cell.
 fn add_to_sprite_sheet(
     char_index: usize,
     base_metrics: &BitmapMetrics,
+    metric_overrides: &MetricOverrides,
     glyph_image: &BitmapGlyph,
     imgbuf: &mut GrayImage,
-) -> Result<(), anyhow::Error> {
+) -> Result<bool, anyhow::Error> {
     // Load the glyph image data into an imgbuf
     let bitmap = match glyph_image.bitmap {
         Bitmap::Embedded(ref bitmap) if bitmap.format == BitDepth::One => bitmap,
@@ -179,17 +435,14 @@ fn add_to_sprite_sheet(
     let height = u32::from(bitmap.height);
     // println!("bitmap is {}x{}", width, height);
     if width == 0 || height == 0 {
-        return Ok(());
+        return Ok(true);
     }
 
-    let expanded = expand_bits(u32::from(width), &bitmap.data);
+    let expanded = expand_bits(width, &bitmap.data);
     let glyph_buffer = GrayImage::from_raw(width, height, expanded).unwrap();
     // println!("loaded image for '{}'", ch);
 
     // Copy the image buffer to the sprite sheet
-    let col = char_index as i32 % CHARS_PER_ROW;
-    let row = char_index as i32 / CHARS_PER_ROW;
-    // TODO: Ensure width and height are the same as all the other characters
     let metrics = match glyph_image.metrics {
         Metrics::Embedded(ref metrics) => metrics.hori(),
         Metrics::HmtxVmtx(_) => {
@@ -197,15 +450,57 @@ fn add_to_sprite_sheet(
         }
     }
     .ok_or_else(|| anyhow!("missing horizontal metrics"))?;
-    let img_x = col * i32::from(base_metrics.advance) + i32::from(metrics.origin_offset_x);
-    let row_height = i32::from(base_metrics.ascender) - i32::from(base_metrics.descender);
-    let img_y = row * row_height + i32::from(base_metrics.ascender)
+    let cell_width = i32::from(base_metrics.advance);
+    let cell_height = i32::from(base_metrics.ascender) - i32::from(base_metrics.descender);
+    let glyph_x =
+        i32::from(metrics.origin_offset_x) + i32::from(metric_overrides.offset_x.unwrap_or(0));
+    let glyph_y = i32::from(base_metrics.ascender)
         - i32::from(bitmap.height)
-        - i32::from(metrics.origin_offset_y);
+        - i32::from(metrics.origin_offset_y)
+        - i32::from(metric_overrides.offset_y.unwrap_or(0));
+
+    let (cell_x, cell_y) = cell_origin(char_index, cell_width as u32, cell_height as u32);
+    let mut in_bounds = true;
+    for (x, y, pixel) in glyph_buffer.enumerate_pixels() {
+        let x = glyph_x + x as i32;
+        let y = glyph_y + y as i32;
+        if (0..cell_width).contains(&x) && (0..cell_height).contains(&y) {
+            imgbuf.put_pixel(cell_x + x as u32, cell_y + y as u32, *pixel);
+        } else if *pixel != Luma([0]) {
+            in_bounds = false;
+        }
+    }
 
-    imgbuf.copy_from(&glyph_buffer, img_x as u32, img_y as u32)?;
+    Ok(in_bounds)
+}
 
-    Ok(())
+/// Reports the glyphs that extend outside their cell.
+///
+/// This is an error unless `clip` is set, in which case the glyphs have already been clipped to
+/// the cell by `add_to_sprite_sheet` and only a warning is printed.
+fn check_out_of_bounds(
+    out_of_bounds: &[char],
+    cell_width: u32,
+    cell_height: u32,
+    clip: bool,
+) -> Result<(), anyhow::Error> {
+    if out_of_bounds.is_empty() {
+        return Ok(());
+    }
+
+    let message = format!(
+        "{} glyph(s) extend outside the {}x{} cell: {}",
+        out_of_bounds.len(),
+        cell_width,
+        cell_height,
+        format_chars(out_of_bounds)
+    );
+    if clip {
+        eprintln!("Warning: clipped {}", message);
+        Ok(())
+    } else {
+        bail!("{}\nUse --clip to clip them to the cell", message)
+    }
 }
 
 // https://github.com/image-rs/image/blob/183e74ea7540da9ce11e613256f8f74640ea70c5/src/utils/mod.rs#L39
@@ -215,12 +510,8 @@ fn expand_bits(row_size: u32, buf: &[u8]) -> Vec<u8> {
     // Note: this conversion assumes that the scanlines begin on byte boundaries
     let mask = (1u8 << bit_depth as usize) - 1;
     let scaling_factor = 255 / ((1 << bit_depth as usize) - 1);
-    let bit_width = row_size * u32::from(bit_depth);
-    let skip = if bit_width % 8 == 0 {
-        0
-    } else {
-        (8 - bit_width % 8) / u32::from(bit_depth)
-    };
+    let bit_width = row_size * bit_depth;
+    let skip = (8 - bit_width % 8) % 8 / bit_depth;
     let row_len = row_size + skip;
     let mut p = Vec::new();
     let mut i = 0;
@@ -251,17 +542,44 @@ fn parse_args(args: Vec<OsString>) -> Result<Args, pico_args::Error> {
             show_help,
             strike: 0,
             font_size: 0,
+            charset: None,
+            fallback: '?',
+            output: None,
+            overrides: None,
+            export_glyphs: None,
+            metric_overrides: NO_OVERRIDES,
+            clip: false,
         });
     }
 
     let strike = args.value_from_str("--strike")?;
     let font_size = args.value_from_str("--font-size")?;
+    let charset = args.opt_value_from_os_str("--charset", parse_path)?;
+    let fallback = args.opt_value_from_str("--fallback")?.unwrap_or('?');
+    let output = args.opt_value_from_os_str("--output", parse_path)?;
+    let overrides = args.opt_value_from_os_str("--overrides", parse_path)?;
+    let export_glyphs = args.opt_value_from_os_str("--export-glyphs", parse_path)?;
+    let metric_overrides = MetricOverrides {
+        advance: args.opt_value_from_str("--advance")?,
+        ascender: args.opt_value_from_str("--ascender")?,
+        descender: args.opt_value_from_str("--descender")?,
+        offset_x: args.opt_value_from_str("--offset-x")?,
+        offset_y: args.opt_value_from_str("--offset-y")?,
+    };
+    let clip = args.contains("--clip");
 
     if args.finish().is_empty() {
         Ok(Args {
             show_help,
             strike,
             font_size,
+            charset,
+            fallback,
+            output,
+            overrides,
+            export_glyphs,
+            metric_overrides,
+            clip,
         })
     } else {
         Err(pico_args::Error::ArgumentParsingFailed {
@@ -269,3 +587,207 @@ fn parse_args(args: Vec<OsString>) -> Result<Args, pico_args::Error> {
         })
     }
 }
+
+fn parse_path(s: &OsStr) -> Result<PathBuf, &'static str> {
+    Ok(s.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glyph(font: &mut SourceFont, ch: char, strike: u8) -> BitmapGlyph {
+        let (glyph_index, _vs) =
+            font.lookup_glyph_index(ch, MatchingPresentation::NotRequired, None);
+        font.lookup_glyph_image(glyph_index, u16::from(strike), BitDepth::One)
+            .unwrap()
+            .unwrap()
+    }
+
+    /// Renders `ch` from `strike` into a single cell, returning the cell and whether it fit.
+    fn render(ch: char, strike: u8, overrides: MetricOverrides) -> (GrayImage, bool) {
+        let (mut font, bitmap_infos) = load_font().unwrap();
+        let mut metrics = space_metrics(&mut font, &bitmap_infos, strike).unwrap();
+        overrides.apply(&mut metrics);
+        let (cell_width, cell_height) = cell_size(&metrics).unwrap();
+        let mut imgbuf = GrayImage::new(cell_width, cell_height);
+        let glyph = glyph(&mut font, ch, strike);
+        let in_bounds = add_to_sprite_sheet(0, &metrics, &overrides, &glyph, &mut imgbuf).unwrap();
+        (imgbuf, in_bounds)
+    }
+
+    fn set_pixels(imgbuf: &GrayImage) -> usize {
+        imgbuf.pixels().filter(|&&pixel| pixel != Luma([0])).count()
+    }
+
+    #[test]
+    fn strike_16_defaults_to_an_advance_of_10() {
+        assert_eq!(
+            NO_OVERRIDES.for_strike(16),
+            MetricOverrides {
+                advance: Some(10),
+                ..NO_OVERRIDES
+            }
+        );
+        assert_eq!(strike_metrics(16).unwrap().advance, 10);
+    }
+
+    #[test]
+    fn other_strikes_have_no_default_overrides() {
+        assert_eq!(NO_OVERRIDES.for_strike(14), NO_OVERRIDES);
+    }
+
+    #[test]
+    fn flags_replace_the_strike_defaults() {
+        let flags = MetricOverrides {
+            advance: Some(9),
+            ..NO_OVERRIDES
+        };
+        assert_eq!(flags.for_strike(16), flags);
+        assert_eq!(flags.for_strike(14), flags);
+
+        // Flags for other metrics keep the default advance
+        let flags = MetricOverrides {
+            ascender: Some(12),
+            offset_y: Some(-1),
+            ..NO_OVERRIDES
+        };
+        assert_eq!(
+            flags.for_strike(16),
+            MetricOverrides {
+                advance: Some(10),
+                ..flags
+            }
+        );
+    }
+
+    #[test]
+    fn each_flag_sets_its_override() {
+        let args = parse_args(
+            [
+                "--strike",
+                "12",
+                "--font-size",
+                "9",
+                "--advance",
+                "7",
+                "--ascender",
+                "10",
+                "--descender",
+                "-3",
+                "--offset-x",
+                "1",
+                "--offset-y",
+                "-2",
+            ]
+            .iter()
+            .map(OsString::from)
+            .collect(),
+        )
+        .unwrap();
+        assert_eq!(
+            args.metric_overrides,
+            MetricOverrides {
+                advance: Some(7),
+                ascender: Some(10),
+                descender: Some(-3),
+                offset_x: Some(1),
+                offset_y: Some(-2),
+            }
+        );
+        assert!(!args.clip);
+    }
+
+    #[test]
+    fn overrides_only_change_the_metrics_they_set() {
+        let mut adjusted = metrics(6, 9, -2);
+        MetricOverrides {
+            descender: Some(-4),
+            offset_x: Some(3),
+            ..NO_OVERRIDES
+        }
+        .apply(&mut adjusted);
+        assert_eq!(
+            (adjusted.advance, adjusted.ascender, adjusted.descender),
+            (6, 9, -4)
+        );
+    }
+
+    #[test]
+    fn glyphs_fit_their_cell_by_default() {
+        let (imgbuf, in_bounds) = render('M', 14, NO_OVERRIDES);
+        assert!(in_bounds);
+        assert!(set_pixels(&imgbuf) > 0);
+    }
+
+    #[test]
+    fn glyphs_moved_outside_the_cell_are_clipped() {
+        let (whole, _) = render('M', 14, NO_OVERRIDES);
+        let (clipped, in_bounds) = render(
+            'M',
+            14,
+            MetricOverrides {
+                offset_x: Some(3),
+                ..NO_OVERRIDES
+            },
+        );
+        assert!(!in_bounds);
+        assert!(set_pixels(&clipped) > 0);
+        assert!(set_pixels(&clipped) < set_pixels(&whole));
+    }
+
+    #[test]
+    fn glyphs_outside_the_cell_are_rejected_without_clip() {
+        assert_eq!(
+            check_out_of_bounds(&['M', 'g'], 6, 11, false)
+                .unwrap_err()
+                .to_string(),
+            "2 glyph(s) extend outside the 6x11 cell: U+004D 'M', U+0067 'g'\n\
+             Use --clip to clip them to the cell"
+        );
+    }
+
+    #[test]
+    fn glyphs_outside_the_cell_are_allowed_with_clip() {
+        assert!(check_out_of_bounds(&['M', 'g'], 6, 11, true).is_ok());
+        assert!(check_out_of_bounds(&[], 6, 11, false).is_ok());
+    }
+
+    fn metrics(advance: u8, ascender: i8, descender: i8) -> BitmapMetrics {
+        BitmapMetrics {
+            origin_offset_x: 0,
+            origin_offset_y: 0,
+            advance,
+            ascender,
+            descender,
+        }
+    }
+
+    #[test]
+    fn cell_size_is_the_advance_by_the_line_height() {
+        assert_eq!(cell_size(&metrics(6, 9, -2)).unwrap(), (6, 11));
+        assert_eq!(cell_size(&metrics(1, -3, -4)).unwrap(), (1, 1));
+    }
+
+    #[test]
+    fn cell_size_widens_extreme_overrides() {
+        assert_eq!(cell_size(&metrics(5, 100, -100)).unwrap(), (5, 200));
+        assert_eq!(cell_size(&metrics(5, 127, -128)).unwrap(), (5, 255));
+    }
+
+    #[test]
+    fn cell_size_rejects_empty_cells() {
+        assert_eq!(
+            cell_size(&metrics(5, -100, 100)).unwrap_err().to_string(),
+            "ascender (-100) must be greater than descender (100)"
+        );
+        assert_eq!(
+            cell_size(&metrics(5, 4, 4)).unwrap_err().to_string(),
+            "ascender (4) must be greater than descender (4)"
+        );
+        assert_eq!(
+            cell_size(&metrics(0, 9, -2)).unwrap_err().to_string(),
+            "advance must be at least 1"
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! Text-art glyph files.
+//!
+//! Glyphs are written as a `U+XXXX` header line, optionally followed by the character itself,
+//! then one line per pixel row of the character cell using `#` for set pixels and `.` for unset
+//! ones. Glyphs are separated by blank lines and lines starting with `//` are comments:
+//!
+//! ```text
+//! // ProFont7Point glyphs, 5x10 pixel cells
+//! U+0021 !
+//! .....
+//! .....
+//! ..#..
+//! ..#..
+//! ..#..
+//! ..#..
+//! .....
+//! ..#..
+//! .....
+//! .....
+//! ```
+//!
+//! Each file holds the glyphs of a single size.
+
+use std::char;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context};
+use image::{GrayImage, Luma};
+
+#[derive(Debug, PartialEq)]
+pub struct Glyph {
+    pub ch: char,
+    pub width: u32,
+    pub height: u32,
+    /// Pixels in row-major order, `true` when set.
+    pub pixels: Vec<bool>,
+}
+
+impl Glyph {
+    /// Reads the glyph in the cell at `(x, y)` of a sprite sheet.
+    pub fn from_sheet(
+        ch: char,
+        sheet: &GrayImage,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let pixels = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (col, row)))
+            .map(|(col, row)| sheet.get_pixel(x + col, y + row).0[0] > 127)
+            .collect();
+        Glyph {
+            ch,
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Writes the glyph into the cell at `(x, y)` of a sprite sheet.
+    pub fn to_sheet(&self, sheet: &mut GrayImage, x: u32, y: u32) {
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let value = if self.pixel(col, row) { 255 } else { 0 };
+                sheet.put_pixel(x + col, y + row, Luma([value]));
+            }
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> bool {
+        self.pixels[(y * self.width + x) as usize]
+    }
+}
+
+pub fn read(path: &Path) -> Result<Vec<Glyph>, anyhow::Error> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("unable to read glyph file {}", path.display()))?;
+    parse(&text).with_context(|| format!("invalid glyph file {}", path.display()))
+}
+
+pub fn parse(text: &str) -> Result<Vec<Glyph>, anyhow::Error> {
+    let mut glyphs: Vec<Glyph> = Vec::new();
+    let mut current: Option<Glyph> = None;
+
+    for (lineno, line) in text.lines().enumerate() {
+        let lineno = lineno + 1;
+        let line = line.trim_end();
+
+        if line.starts_with("//") {
+            continue;
+        } else if line.starts_with("U+") {
+            glyphs.extend(current.take().map(finish).transpose()?);
+            current = Some(Glyph {
+                ch: parse_header(line).map_err(|err| anyhow!("line {}: {}", lineno, err))?,
+                width: 0,
+                height: 0,
+                pixels: Vec::new(),
+            });
+        } else if line.is_empty() {
+            glyphs.extend(current.take().map(finish).transpose()?);
+        } else if let Some(glyph) = current.as_mut() {
+            let row = line
+                .chars()
+                .map(|c| match c {
+                    '#' => Ok(true),
+                    '.' => Ok(false),
+                    _ => Err(anyhow!("line {}: unexpected '{}' in glyph row", lineno, c)),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if glyph.height == 0 {
+                glyph.width = row.len() as u32;
+            } else if row.len() as u32 != glyph.width {
+                bail!(
+                    "line {}: row of U+{:04X} is {} pixels wide, expected {}",
+                    lineno,
+                    glyph.ch as u32,
+                    row.len(),
+                    glyph.width
+                );
+            }
+            glyph.pixels.extend(row);
+            glyph.height += 1;
+        } else {
+            bail!("line {}: expected a U+XXXX glyph header", lineno);
+        }
+    }
+    glyphs.extend(current.take().map(finish).transpose()?);
+
+    // All of the glyphs in a file are the same size
+    if let Some(first) = glyphs.first() {
+        if let Some(glyph) = glyphs
+            .iter()
+            .find(|glyph| (glyph.width, glyph.height) != (first.width, first.height))
+        {
+            bail!(
+                "U+{:04X} is {}x{}, expected {}x{} like U+{:04X}",
+                glyph.ch as u32,
+                glyph.width,
+                glyph.height,
+                first.width,
+                first.height,
+                first.ch as u32
+            );
+        }
+    }
+
+    Ok(glyphs)
+}
+
+fn parse_header(line: &str) -> Result<char, String> {
+    let code_point = line.split_whitespace().next().unwrap_or(line);
+    code_point
+        .strip_prefix("U+")
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .and_then(char::from_u32)
+        .ok_or_else(|| format!("invalid code point '{}'", code_point))
+}
+
+fn finish(glyph: Glyph) -> Result<Glyph, anyhow::Error> {
+    if glyph.height == 0 {
+        bail!("U+{:04X} has no rows", glyph.ch as u32);
+    }
+    Ok(glyph)
+}
+
+pub fn write(path: &Path, header: &str, glyphs: &[Glyph]) -> Result<(), anyhow::Error> {
+    fs::write(path, format(header, glyphs))
+        .with_context(|| format!("unable to write glyph file {}", path.display()))
+}
+
+pub fn format(header: &str, glyphs: &[Glyph]) -> String {
+    let mut text = String::new();
+    for line in header.lines() {
+        let _ = writeln!(text, "// {}", line);
+    }
+
+    for glyph in glyphs {
+        text.push('\n');
+        let _ = write!(text, "U+{:04X}", glyph.ch as u32);
+        if !glyph.ch.is_whitespace() && !glyph.ch.is_control() {
+            let _ = write!(text, " {}", glyph.ch);
+        }
+        text.push('\n');
+
+        for row in glyph.pixels.chunks(glyph.width as usize) {
+            text.extend(row.iter().map(|&set| if set { '#' } else { '.' }));
+            text.push('\n');
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXCLAMATION: &str = "\
+U+0021 !
+.....
+..#..
+..#..
+.....
+..#..
+";
+
+    fn glyph(ch: char, rows: &[&str]) -> Glyph {
+        Glyph {
+            ch,
+            width: rows[0].len() as u32,
+            height: rows.len() as u32,
+            pixels: rows
+                .iter()
+                .flat_map(|row| row.chars().map(|c| c == '#'))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn format_then_parse_round_trips() {
+        let glyphs = vec![
+            glyph('!', &["..#..", "..#..", ".....", "..#.."]),
+            glyph(' ', &[".....", ".....", ".....", "....."]),
+            glyph('é', &["..##.", ".###.", "#####", ".###."]),
+            glyph('\u{7F}', &["#####", "#...#", "#...#", "#####"]),
+        ];
+        let text = format("Test glyphs\n4x4 cells", &glyphs);
+        assert!(text.starts_with("// Test glyphs\n// 4x4 cells\n\nU+0021 !\n..#..\n"));
+        assert_eq!(parse(&text).unwrap(), glyphs);
+    }
+
+    #[test]
+    fn parses_comments_and_trailing_whitespace() {
+        let text = format!(
+            "// header\n\n{}\n\n// more\nU+0041  \n#....  \n.#...\n..#..\n...#.\n....#\n",
+            EXCLAMATION
+        );
+        let glyphs = parse(&text).unwrap();
+        assert_eq!(glyphs.len(), 2);
+        assert_eq!(glyphs[0].ch, '!');
+        assert!(glyphs[0].pixel(2, 1) && !glyphs[0].pixel(2, 3));
+        assert_eq!(
+            glyphs[1],
+            glyph('A', &["#....", ".#...", "..#..", "...#.", "....#"])
+        );
+    }
+
+    #[test]
+    fn rows_of_the_wrong_width_are_rejected() {
+        let err = parse("U+0041\n...\n.#.\n..\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 4: row of U+0041 is 2 pixels wide, expected 3"
+        );
+    }
+
+    #[test]
+    fn glyphs_of_the_wrong_size_are_rejected() {
+        let text = format!("{}\nU+0042\n.....\n.....\n", EXCLAMATION);
+        assert_eq!(
+            parse(&text).unwrap_err().to_string(),
+            "U+0042 is 5x2, expected 5x5 like U+0021"
+        );
+
+        let text = format!("{}\nU+0042\n......\n", EXCLAMATION);
+        assert_eq!(
+            parse(&text).unwrap_err().to_string(),
+            "U+0042 is 6x1, expected 5x5 like U+0021"
+        );
+    }
+
+    #[test]
+    fn malformed_glyphs_are_rejected() {
+        let cases = [
+            ("U+0041\n.x\n", "line 2: unexpected 'x' in glyph row"),
+            ("U+0041\n\nU+0042\n#\n", "U+0041 has no rows"),
+            ("#.\n", "line 1: expected a U+XXXX glyph header"),
+            ("U+D800\n#\n", "line 1: invalid code point 'U+D800'"),
+        ];
+        for &(text, message) in cases.iter() {
+            assert_eq!(parse(text).unwrap_err().to_string(), message, "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+
+use anyhow::{bail, Context};
+use embedded_graphics::{
+    mono_font::{mapping::GlyphMapping, MonoFont, MonoTextStyle},
+    pixelcolor::{BinaryColor, Rgb888},
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+};
+use embedded_graphics_simulator::{OutputSettings, SimulatorDisplay};
+use pico_args::Arguments;
+use profont::{GLYPH_MAPPING, PROFONT_7_POINT, PROFONT_9_POINT};
+
+use crate::generate::CHARS_PER_ROW;
+use crate::render;
+use crate::sizes::{self, FontSize, SIZES};
+
+const HELP_MESSAGE: &str = "\
+profont grid
+
+Writes a labelled character grid of each size for reviewing and printing. Cells
+are laid out by code point, 32 to a row like the sprite sheets, with the code
+point under each cell and row and column headers. Code points that aren't in
+the font are shaded and the fallback glyph is outlined.
+
+USAGE:
+    grid [OPTIONS]
+
+FLAGS:
+  -h, --help                Prints help information
+
+OPTIONS:
+      --size        POINTS  Only write the grid of this size
+  -o, --output-dir  DIR     Directory to write ProFont<N>PointGrid.png files to [default: .]
+      --scale       N       Size of each font pixel [default: 2]
+";
+const MARGIN: i32 = 16;
+/// Space around the glyph and label inside each cell.
+const PADDING: i32 = 3;
+const BACKGROUND_COLOR: Rgb888 = Rgb888::WHITE;
+const HEADER_COLOR: Rgb888 = Rgb888::new(0x60, 0x60, 0x60);
+const CELL_COLOR: Rgb888 = Rgb888::new(0xf0, 0xf0, 0xf0);
+const MISSING_COLOR: Rgb888 = Rgb888::new(0xf8, 0xd0, 0xd0);
+const LABEL_COLOR: Rgb888 = Rgb888::new(0x80, 0x80, 0x80);
+const MISSING_LABEL_COLOR: Rgb888 = Rgb888::new(0xc0, 0x30, 0x30);
+const INK_COLOR: Rgb888 = Rgb888::BLACK;
+const FALLBACK_COLOR: Rgb888 = Rgb888::CSS_DARK_ORANGE;
+
+struct Args {
+    show_help: bool,
+    size: Option<u8>,
+    output_dir: PathBuf,
+    scale: u32,
+}
+
+pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
+    let args = parse_args(args)?;
+
+    if args.show_help {
+        println!("{}", HELP_MESSAGE);
+        return Ok(());
+    }
+
+    let selected = match args.size {
+        Some(point) => vec![sizes::find(point)?],
+        None => SIZES.iter().collect(),
+    };
+    for size in selected {
+        let path = args.output_dir.join(format!("{}Grid.png", size.name()));
+        draw(size, args.scale)
+            .to_rgb_output_image(&OutputSettings::default())
+            .save_png(&path)
+            .with_context(|| format!("unable to write {}", path.display()))?;
+        println!("Wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Draws the grid of every code point from the start of the row containing the first mapped
+/// character to the last mapped character.
+fn draw(size: &FontSize, scale: u32) -> SimulatorDisplay<Rgb888> {
+    let font = &size.font;
+    let cell = font.character_size;
+    let header_style = MonoTextStyle::new(&PROFONT_9_POINT, HEADER_COLOR);
+    let header_height = PROFONT_9_POINT.character_size.height as i32;
+    let label_height = PROFONT_7_POINT.character_size.height as i32;
+
+    let mut chars = GLYPH_MAPPING.chars();
+    let first = chars.next().map_or(0, |ch| ch as u32);
+    let last = chars.last().map_or(first, |ch| ch as u32);
+    let first_row = first / CHARS_PER_ROW as u32;
+    let rows = (last / CHARS_PER_ROW as u32 - first_row + 1) as i32;
+    let fallback = GLYPH_MAPPING.chars().nth(GLYPH_MAPPING.index('\u{ffff}'));
+
+    // Labels are wide enough for the largest code point, and cells for the glyph or its label
+    let label_chars = format!("{:02X}", last).len();
+    let glyph_size = cell * scale;
+    let column_width = glyph_size
+        .width
+        .max(render::text_width(&PROFONT_7_POINT, label_chars)) as i32
+        + PADDING * 2;
+    let row_height = glyph_size.height as i32 + label_height + PADDING * 3;
+    let row_header_width = render::text_width(&PROFONT_9_POINT, "U+0000".len()) as i32 + PADDING;
+
+    let title = format!(
+        "ProFont {}pt, {}x{} pixel cells",
+        size.point, cell.width, cell.height
+    );
+    let legend = match fallback {
+        Some(fallback) => format!(
+            "shaded: not in the font   outlined: fallback glyph U+{:04X}",
+            fallback as u32
+        ),
+        None => "shaded: not in the font".to_string(),
+    };
+
+    let grid_origin = Point::new(MARGIN + row_header_width, MARGIN + header_height * 3);
+    let width = grid_origin.x + column_width * CHARS_PER_ROW + MARGIN;
+    let height = grid_origin.y + row_height * rows + header_height + MARGIN;
+    let mut display = SimulatorDisplay::<Rgb888>::new(Size::new(width as u32, height as u32));
+    display.clear(BACKGROUND_COLOR).unwrap();
+
+    Text::with_baseline(
+        &title,
+        Point::new(MARGIN, MARGIN),
+        header_style,
+        Baseline::Top,
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    for column in 0..CHARS_PER_ROW {
+        let header = format!("{:02X}", column);
+        let x = grid_origin.x
+            + column * column_width
+            + (column_width - render::text_width(&PROFONT_9_POINT, header.len()) as i32) / 2;
+        Text::with_baseline(
+            &header,
+            Point::new(x, grid_origin.y - header_height - PADDING),
+            header_style,
+            Baseline::Top,
+        )
+        .draw(&mut display)
+        .unwrap();
+    }
+
+    for row in 0..rows {
+        let top = grid_origin.y + row * row_height;
+        let row_start = (first_row + row as u32) * CHARS_PER_ROW as u32;
+        Text::with_baseline(
+            &format!("U+{:04X}", row_start),
+            Point::new(MARGIN, top + PADDING),
+            header_style,
+            Baseline::Top,
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        for column in 0..CHARS_PER_ROW {
+            let origin = Point::new(grid_origin.x + column * column_width, top);
+            let ch = std::char::from_u32(row_start + column as u32);
+            draw_cell(
+                font,
+                ch,
+                ch.is_some() && ch == fallback,
+                origin,
+                Size::new(column_width as u32, row_height as u32),
+                scale,
+                &mut display,
+            );
+        }
+    }
+
+    Text::with_baseline(
+        &legend,
+        Point::new(MARGIN, height - MARGIN - header_height),
+        header_style,
+        Baseline::Top,
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    display
+}
+
+/// Draws one cell of the grid: the enlarged glyph with its code point underneath.
+fn draw_cell(
+    font: &MonoFont,
+    ch: Option<char>,
+    is_fallback: bool,
+    origin: Point,
+    size: Size,
+    scale: u32,
+    display: &mut SimulatorDisplay<Rgb888>,
+) {
+    let mapped = ch.map_or(false, |ch| GLYPH_MAPPING.contains(ch));
+    let glyph_size = font.character_size * scale;
+    let glyph_origin = origin + Point::new((size.width - glyph_size.width) as i32 / 2, PADDING);
+
+    // Leave a pixel between cells so that they stay distinct when printed
+    let background = if mapped { CELL_COLOR } else { MISSING_COLOR };
+    Rectangle::new(origin, size - Size::new(1, 1))
+        .into_styled(PrimitiveStyle::with_fill(background))
+        .draw(display)
+        .unwrap();
+
+    if let (Some(ch), true) = (ch, mapped) {
+        let mut glyph = SimulatorDisplay::<BinaryColor>::new(font.character_size);
+        let mut text = [0; 4];
+        Text::with_baseline(
+            ch.encode_utf8(&mut text),
+            Point::zero(),
+            MonoTextStyle::new(font, BinaryColor::On),
+            Baseline::Top,
+        )
+        .draw(&mut glyph)
+        .unwrap();
+
+        Rectangle::new(glyph_origin, glyph_size)
+            .into_styled(PrimitiveStyle::with_fill(BACKGROUND_COLOR))
+            .draw(display)
+            .unwrap();
+        for point in glyph.bounding_box().points() {
+            if glyph.get_pixel(point) == BinaryColor::On {
+                Rectangle::new(glyph_origin + point * scale as i32, Size::new_equal(scale))
+                    .into_styled(PrimitiveStyle::with_fill(INK_COLOR))
+                    .draw(display)
+                    .unwrap();
+            }
+        }
+    }
+
+    let label = ch.map_or_else(String::new, |ch| format!("{:02X}", ch as u32));
+    let label_color = if mapped {
+        LABEL_COLOR
+    } else {
+        MISSING_LABEL_COLOR
+    };
+    let label_x = origin.x
+        + (size.width as i32 - render::text_width(&PROFONT_7_POINT, label.len()) as i32) / 2;
+    Text::with_baseline(
+        &label,
+        Point::new(label_x, glyph_origin.y + glyph_size.height as i32 + PADDING),
+        MonoTextStyle::new(&PROFONT_7_POINT, label_color),
+        Baseline::Top,
+    )
+    .draw(display)
+    .unwrap();
+
+    if is_fallback {
+        Rectangle::new(origin, size - Size::new(1, 1))
+            .into_styled(PrimitiveStyle::with_stroke(FALLBACK_COLOR, 2))
+            .draw(display)
+            .unwrap();
+    }
+}
+
+fn parse_args(args: Vec<OsString>) -> Result<Args, anyhow::Error> {
+    let mut args = Arguments::from_vec(args);
+
+    let show_help = args.contains(["-h", "--help"]);
+    let size = args.opt_value_from_str("--size")?;
+    let output_dir = args
+        .opt_value_from_os_str(["-o", "--output-dir"], parse_path)?
+        .unwrap_or_else(|| PathBuf::from("."));
+    let scale = args.opt_value_from_str("--scale")?.unwrap_or(2);
+    if scale == 0 {
+        bail!("--scale must be at least 1");
+    }
+
+    if !args.finish().is_empty() {
+        bail!("Unknown arguments. Use '--help' for help on usage.");
+    }
+
+    Ok(Args {
+        show_help,
+        size,
+        output_dir,
+        scale,
+    })
+}
+
+fn parse_path(s: &OsStr) -> Result<PathBuf, &'static str> {
+    Ok(s.into())
+}
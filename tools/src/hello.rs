@@ -6,11 +6,25 @@ use embedded_graphics::{
     text::{Baseline, Text, TextStyle},
 };
 use embedded_graphics_simulator::{OutputSettingsBuilder, SimulatorDisplay, Window};
+use pico_args::Arguments;
 use profont::PROFONT_12_POINT;
 
+use crate::profiles::{self, DisplayProfile};
+use crate::render;
 use crate::style;
 
+const PROFILE_HELP: &str = "\
+      --profile NAME    Show the text on a display profile, see 'profont profiles'
+";
+
 pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
+    let (profile, args) = match parse_profile(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
     let (args, free_args) = match style::parse_args(args) {
         Ok(args) => args,
         Err(e) => {
@@ -21,16 +35,18 @@ pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
 
     if args.show_help {
         println!(
-            "{}",
+            "{}{}",
             style::help(
                 "hello",
                 "A simple hello world example using the 12pt ProFont font"
-            )
+            ),
+            PROFILE_HELP
         );
         return Ok(());
     }
 
-    let mut display: SimulatorDisplay<BinaryColor> = SimulatorDisplay::new(Size::new(500, 128));
+    let size = profile.map_or(Size::new(500, 128), |profile| profile.size);
+    let mut display: SimulatorDisplay<BinaryColor> = SimulatorDisplay::new(size);
 
     let character_style = style::binary_builder(&args).font(&PROFONT_12_POINT).build();
     let text_style = TextStyle::with_baseline(Baseline::Top);
@@ -38,17 +54,41 @@ pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
         .first()
         .and_then(|arg| arg.to_str())
         .unwrap_or("Hello world!");
+    // Wrap the text to the panel, as it would have to be on the device
+    let columns = profile.map(|profile| profile.text_grid(&PROFONT_12_POINT).0);
+    let test_text = render::wrap(test_text, columns).join("\n");
 
-    Text::with_text_style(test_text, Point::zero(), character_style, text_style)
+    Text::with_text_style(&test_text, Point::zero(), character_style, text_style)
         .draw(&mut display)?;
 
-    let output_settings = OutputSettingsBuilder::new()
-        // TODO: Add theme to StyleArgs
-        // .theme(BinaryColorTheme::OledBlue)
-        .scale(u32::from(args.gui_scale))
-        .build();
+    let mut output_settings = OutputSettingsBuilder::new().scale(u32::from(args.gui_scale));
+    let mut window = match profile {
+        Some(profile) => {
+            profiles::print_profile(profile);
+            output_settings = output_settings.theme(profile.theme);
+            let title = format!("ProFont hello world - {}", profile.description);
+            Window::new(&title, &output_settings.build())
+        }
+        None => Window::new("ProFont hello world", &output_settings.build()),
+    };
 
-    Window::new("ProFont hello world", &output_settings).show_static(&display);
+    match profile {
+        // The first line is the heading, which is red on panels with red ink
+        Some(profile) => {
+            window.show_static(&profile.colorize(&display, PROFONT_12_POINT.character_size.height))
+        }
+        None => window.show_static(&display),
+    }
 
     Ok(())
 }
+
+/// Takes the `--profile` option out of the arguments, returning the profile and the remaining
+/// arguments.
+fn parse_profile(
+    args: Vec<OsString>,
+) -> Result<(Option<&'static DisplayProfile>, Vec<OsString>), pico_args::Error> {
+    let mut args = Arguments::from_vec(args);
+    let profile = args.opt_value_from_fn("--profile", profiles::find)?;
+    Ok((profile, args.finish()))
+}
@@ -0,0 +1,310 @@
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail};
+use embedded_graphics::{
+    mono_font::{mapping::GlyphMapping, DecorationDimensions, MonoFont, MonoTextStyle},
+    pixelcolor::{BinaryColor, Rgb888},
+    prelude::*,
+    primitives::{Line, PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+};
+use embedded_graphics_simulator::{
+    OutputSettings, OutputSettingsBuilder, SimulatorDisplay, Window,
+};
+use pico_args::Arguments;
+use profont::{GLYPH_MAPPING, PROFONT_9_POINT};
+
+use crate::charset;
+use crate::render;
+use crate::sizes::{FontSize, SIZES};
+
+const HELP_MESSAGE: &str = "\
+profont inspect-glyph
+
+Shows one character enlarged in all sizes side by side, with its pixel grid,
+cell box, baseline, underline and strikethrough rows.
+
+USAGE:
+    inspect-glyph [OPTIONS] CHAR
+
+CHAR is either the character itself or its code point written as U+XXXX.
+
+FLAGS:
+  -h, --help            Prints help information
+
+OPTIONS:
+  -o, --output  FILE    Write a PNG instead of opening a window
+      --zoom    N       Size of each glyph pixel [default: 8]
+";
+const MARGIN: i32 = 10;
+const GAP: i32 = 16;
+const BACKGROUND_COLOR: Rgb888 = Rgb888::BLACK;
+const TEXT_COLOR: Rgb888 = Rgb888::CSS_LIGHT_GRAY;
+const GRID_COLOR: Rgb888 = Rgb888::new(0x30, 0x30, 0x30);
+const CELL_COLOR: Rgb888 = Rgb888::new(0x18, 0x18, 0x18);
+const INK_COLOR: Rgb888 = Rgb888::WHITE;
+const CELL_BOX_COLOR: Rgb888 = Rgb888::CSS_GOLD;
+const BASELINE_COLOR: Rgb888 = Rgb888::RED;
+const UNDERLINE_COLOR: Rgb888 = Rgb888::new(0x1c, 0x2c, 0x58);
+const STRIKETHROUGH_COLOR: Rgb888 = Rgb888::new(0x58, 0x20, 0x18);
+
+struct Args {
+    show_help: bool,
+    ch: char,
+    output: Option<PathBuf>,
+    zoom: u32,
+}
+
+pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
+    let args = parse_args(args)?;
+
+    if args.show_help {
+        println!("{}", HELP_MESSAGE);
+        return Ok(());
+    }
+
+    let display = draw(args.ch, args.zoom);
+
+    match args.output {
+        Some(path) => {
+            display
+                .to_rgb_output_image(&OutputSettings::default())
+                .save_png(&path)?;
+            println!("Wrote {}", path.display());
+        }
+        None => {
+            let output_settings = OutputSettingsBuilder::new().scale(1).build();
+            let title = format!("ProFont U+{:04X}", args.ch as u32);
+            Window::new(&title, &output_settings).show_static(&display);
+        }
+    }
+
+    Ok(())
+}
+
+/// Draws `ch` in every size, with a description of the glyph above and a legend below.
+fn draw(ch: char, zoom: u32) -> SimulatorDisplay<Rgb888> {
+    let text_style = MonoTextStyle::new(&PROFONT_9_POINT, TEXT_COLOR);
+    let line_height = PROFONT_9_POINT.character_size.height as i32;
+
+    let description = describe_glyph(ch);
+    let captions = SIZES.iter().map(caption).collect::<Vec<_>>();
+    let legend =
+        "gold: cell box   red: baseline   blue: underline rows   brown: strikethrough rows";
+
+    // Each panel is as wide as its enlarged glyph, or its caption if that is wider
+    let panel_widths = SIZES
+        .iter()
+        .zip(&captions)
+        .map(|(size, caption)| {
+            let caption_width = caption
+                .lines()
+                .map(|line| render::text_width(&PROFONT_9_POINT, line.chars().count()))
+                .max()
+                .unwrap_or(0);
+            (size.font.character_size.width * zoom + 1).max(caption_width) as i32
+        })
+        .collect::<Vec<_>>();
+    let panels_height = SIZES
+        .iter()
+        .map(|size| size.font.character_size.height * zoom + 1)
+        .max()
+        .unwrap_or(0) as i32;
+    let caption_height = 4 * line_height;
+
+    let width = (MARGIN * 2 + panel_widths.iter().sum::<i32>() + GAP * (SIZES.len() as i32 - 1))
+        .max(MARGIN * 2 + render::text_width(&PROFONT_9_POINT, legend.chars().count()) as i32);
+    let height = MARGIN * 2 + line_height * 3 + panels_height + caption_height + line_height;
+    let mut display = SimulatorDisplay::<Rgb888>::new(Size::new(width as u32, height as u32));
+    display.clear(BACKGROUND_COLOR).unwrap();
+
+    Text::with_baseline(
+        &description,
+        Point::new(MARGIN, MARGIN),
+        text_style,
+        Baseline::Top,
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    let top = MARGIN + line_height * 2;
+    let mut left = MARGIN;
+    for ((size, caption), panel_width) in SIZES.iter().zip(&captions).zip(&panel_widths) {
+        Text::with_baseline(caption, Point::new(left, top), text_style, Baseline::Top)
+            .draw(&mut display)
+            .unwrap();
+        draw_glyph(
+            &size.font,
+            ch,
+            zoom,
+            Point::new(left, top + caption_height),
+            &mut display,
+        );
+        left += panel_width + GAP;
+    }
+
+    Text::with_baseline(
+        legend,
+        Point::new(MARGIN, height - MARGIN - line_height),
+        text_style,
+        Baseline::Top,
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    display
+}
+
+/// Returns the code point and sprite sheet position of a character.
+fn describe_glyph(ch: char) -> String {
+    let index = GLYPH_MAPPING.index(ch);
+    // The caption is drawn in ProFont, so only show characters it has a glyph for
+    let shown = if ch.is_whitespace() || ch.is_control() || !GLYPH_MAPPING.contains(ch) {
+        String::new()
+    } else {
+        format!(" '{}'", ch)
+    };
+    if GLYPH_MAPPING.contains(ch) {
+        format!("U+{:04X}{}  glyph index {}", ch as u32, shown, index)
+    } else {
+        let fallback = GLYPH_MAPPING.chars().nth(index).unwrap_or('?');
+        format!(
+            "U+{:04X}{}  not in the font, drawn as the fallback glyph {} '{}'",
+            ch as u32, shown, index, fallback
+        )
+    }
+}
+
+fn caption(size: &FontSize) -> String {
+    let font = &size.font;
+    format!(
+        "{}pt {}x{}\nbaseline {}\nunderline {}\nstrike {}",
+        size.point,
+        font.character_size.width,
+        font.character_size.height,
+        font.baseline,
+        describe_decoration(&font.underline),
+        describe_decoration(&font.strikethrough)
+    )
+}
+
+fn describe_decoration(decoration: &DecorationDimensions) -> String {
+    if decoration.height > 1 {
+        format!(
+            "{}-{}",
+            decoration.offset,
+            decoration.offset + decoration.height - 1
+        )
+    } else {
+        decoration.offset.to_string()
+    }
+}
+
+/// Draws the glyph for `ch` enlarged by `zoom`, with the top left corner of the grid at `origin`.
+fn draw_glyph(
+    font: &MonoFont,
+    ch: char,
+    zoom: u32,
+    origin: Point,
+    display: &mut SimulatorDisplay<Rgb888>,
+) {
+    let cell = font.character_size;
+    let mut glyph = SimulatorDisplay::<BinaryColor>::new(cell);
+    let mut text = [0; 4];
+    Text::with_baseline(
+        ch.encode_utf8(&mut text),
+        Point::zero(),
+        MonoTextStyle::new(font, BinaryColor::On),
+        Baseline::Top,
+    )
+    .draw(&mut glyph)
+    .unwrap();
+
+    let in_rows = |decoration: &DecorationDimensions, y: u32| {
+        (decoration.offset..decoration.offset + decoration.height).contains(&y)
+    };
+
+    // The grid lines are the gaps between the pixels
+    let grid_size = Size::new(cell.width * zoom + 1, cell.height * zoom + 1);
+    Rectangle::new(origin, grid_size)
+        .into_styled(PrimitiveStyle::with_fill(GRID_COLOR))
+        .draw(display)
+        .unwrap();
+    for point in glyph.bounding_box().points() {
+        let y = point.y as u32;
+        let color = if glyph.get_pixel(point) == BinaryColor::On {
+            INK_COLOR
+        } else if in_rows(&font.underline, y) {
+            UNDERLINE_COLOR
+        } else if in_rows(&font.strikethrough, y) {
+            STRIKETHROUGH_COLOR
+        } else {
+            CELL_COLOR
+        };
+        Rectangle::new(
+            origin + point * zoom as i32 + Point::new(1, 1),
+            Size::new_equal(zoom - 1),
+        )
+        .into_styled(PrimitiveStyle::with_fill(color))
+        .draw(display)
+        .unwrap();
+    }
+
+    // The baseline runs along the bottom of the baseline row
+    let baseline_y = origin.y + ((font.baseline + 1) * zoom) as i32;
+    Line::new(
+        Point::new(origin.x - 4, baseline_y),
+        Point::new(origin.x + grid_size.width as i32 + 3, baseline_y),
+    )
+    .into_styled(PrimitiveStyle::with_stroke(BASELINE_COLOR, 1))
+    .draw(display)
+    .unwrap();
+
+    Rectangle::new(origin, grid_size)
+        .into_styled(PrimitiveStyle::with_stroke(CELL_BOX_COLOR, 1))
+        .draw(display)
+        .unwrap();
+}
+
+fn parse_args(args: Vec<OsString>) -> Result<Args, anyhow::Error> {
+    let mut args = Arguments::from_vec(args);
+
+    let show_help = args.contains(["-h", "--help"]);
+    if show_help {
+        return Ok(Args {
+            show_help,
+            ch: ' ',
+            output: None,
+            zoom: 0,
+        });
+    }
+
+    let output = args.opt_value_from_os_str(["-o", "--output"], parse_path)?;
+    let zoom = args.opt_value_from_str("--zoom")?.unwrap_or(8);
+    if zoom < 2 {
+        bail!("--zoom must be at least 2 to leave room for the pixel grid");
+    }
+
+    let mut free_args = args.finish();
+    if free_args.len() != 1 {
+        bail!("Expected a single CHAR. Use '--help' for help on usage.");
+    }
+    let ch = free_args
+        .pop()
+        .unwrap()
+        .into_string()
+        .map_err(|_| anyhow!("CHAR is not valid UTF-8"))?;
+    let ch = charset::parse_char(&ch).map_err(|err| anyhow!(err))?;
+
+    Ok(Args {
+        show_help,
+        ch,
+        output,
+        zoom,
+    })
+}
+
+fn parse_path(s: &OsStr) -> Result<PathBuf, &'static str> {
+    Ok(s.into())
+}
@@ -1,8 +1,26 @@
+mod charset;
+mod check_metrics;
+mod compare;
 mod debugger;
+mod diff;
+mod draw_calls;
+mod edit_glyph;
+mod epaper;
 mod generate;
+mod glyph_art;
+mod grid;
 mod hello;
+mod inspect_glyph;
 mod mock_display;
+mod preview;
+mod profiles;
+mod raw;
+mod render;
+mod size_report;
+mod sizes;
+mod specimen;
 mod style;
+mod terminal_graphics;
 
 use anyhow::{anyhow, bail};
 
@@ -15,10 +33,23 @@ pub fn main() -> Result<(), anyhow::Error> {
     let args = args.collect::<Vec<_>>();
 
     match cmd.to_str() {
+        Some("check-metrics") => check_metrics::main(args),
+        Some("compare") => compare::main(args),
         Some("debugger") => debugger::main(args),
+        Some("diff") => diff::main(args),
+        Some("draw-calls") => draw_calls::main(args),
+        Some("edit-glyph") => edit_glyph::main(args),
+        Some("epaper") => epaper::main(args),
         Some("generate") => generate::main(args),
+        Some("grid") => grid::main(args),
         Some("hello") => hello::main(args),
+        Some("inspect-glyph") => inspect_glyph::main(args),
         Some("mock-display") => mock_display::main(args),
+        Some("preview") => preview::main(args),
+        Some("profiles") => profiles::main(args),
+        Some("render") => render::main(args),
+        Some("size-report") => size_report::main(args),
+        Some("specimen") => specimen::main(args),
         _ => {
             usage();
             bail!("invalid command")
@@ -32,10 +63,23 @@ Usage: profont <command>
 
 Where <command> is one of:
 
+  check-metrics   Check the font metrics against the glyph bitmaps
+  compare         Compare ProFont with the built-in embedded-graphics fonts
   debugger        Render sample text in all sizes
+  diff            Compare two versions of the sprite sheets
+  draw-calls      Report the draw calls made for text in each size and style
+  edit-glyph      Edit the pixels of a glyph
+  epaper          Simulate text on an e-paper panel
   generate        Regenerate the font
+  grid            Write labelled character grids of each size
   hello           Show hello world text on simulated display
+  inspect-glyph   Show one character enlarged in all sizes
   mock-display    Render text to the terminal
+  preview         Render text to the terminal with block or braille characters
+  profiles        List display profiles and the text grid of each size
+  render          Render text to a PNG
+  size-report     Report the flash used by each size
+  specimen        Write an HTML specimen page of all sizes
 
 Each command may also have its own options, and accepts --help.
 ";
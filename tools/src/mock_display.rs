@@ -1,40 +1,124 @@
 use std::ffi::OsString;
 
+use anyhow::{anyhow, bail};
 use embedded_graphics::{
-    mock_display::MockDisplay, mono_font::MonoTextStyle, pixelcolor::Rgb888, prelude::*, text::Text,
+    mono_font::MonoTextStyleBuilder,
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::{Baseline, Text},
 };
-use profont::PROFONT_7_POINT;
+use pico_args::Arguments;
+use profont::testing::RecordingDisplay;
+
+use crate::render;
+use crate::sizes;
 
 const HELP_MESSAGE: &str = "\
 profont mock-display
 
-Uses the embedded-graphics `MockDisplay` to render output without requiring
-a display device.
+Renders text as ASCII art without requiring a display device, for pasting
+glyph renderings into issues and tests.
 
 USAGE:
     mock-display [OPTIONS] [TEXT]
 
 FLAGS:
-  -h, --help    Prints help information
+  -h, --help            Prints help information
+  -s, --strikethrough   Enables strikethrough style
+  -u, --underline       Enables underline style
+
+OPTIONS:
+      --size    POINTS  ProFont size to render with [default: 7]
+      --width   PX      Wrap the text to fit this many pixels
+      --on      CHAR    Character for set pixels [default: #]
+      --off     CHAR    Character for unset pixels [default: .]
 ";
 
+struct Args {
+    show_help: bool,
+    text: String,
+    size: u8,
+    underline: bool,
+    strikethrough: bool,
+    width: Option<u32>,
+    on: char,
+    off: char,
+}
+
 pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
-    if args.iter().any(|arg| arg.to_str() == Some("--help")) {
+    let args = parse_args(args)?;
+
+    if args.show_help {
         println!("{}", HELP_MESSAGE);
         return Ok(());
     }
 
-    let mut display = MockDisplay::new();
+    let font = &sizes::find(args.size)?.font;
+    let mut style = MonoTextStyleBuilder::new()
+        .font(font)
+        .text_color(BinaryColor::On)
+        // Draw the unset pixels too, so that the output covers whole character cells
+        .background_color(BinaryColor::Off);
+    if args.underline {
+        style = style.underline();
+    }
+    if args.strikethrough {
+        style = style.strikethrough();
+    }
 
-    let text_style = MonoTextStyle::new(&PROFONT_7_POINT, Rgb888::RED);
-    let text = args
-        .first()
-        .and_then(|arg| arg.to_str())
-        .unwrap_or("Hello world");
-    Text::new(text, Point::new(0, 7), text_style).draw(&mut display)?;
+    let columns = args
+        .width
+        .map(|width| render::columns_for_width(font, width));
+    let text = render::wrap(&args.text, columns).join("\n");
 
-    // Print the mock display contents to the terminal
-    println!("{:?}", display);
+    let mut display = RecordingDisplay::new();
+    Text::with_baseline(&text, Point::zero(), style.build(), Baseline::Top).draw(&mut display)?;
+
+    // Swap in the chosen characters, showing pixels that weren't drawn as unset
+    let art = display
+        .to_ascii_art()
+        .chars()
+        .map(|c| match c {
+            '#' => args.on,
+            '.' | ' ' => args.off,
+            _ => c,
+        })
+        .collect::<String>();
+    print!("{}", art);
 
     Ok(())
 }
+
+fn parse_args(args: Vec<OsString>) -> Result<Args, anyhow::Error> {
+    let mut args = Arguments::from_vec(args);
+
+    let show_help = args.contains(["-h", "--help"]);
+    let underline = args.contains(["-u", "--underline"]);
+    let strikethrough = args.contains(["-s", "--strikethrough"]);
+    let size = args.opt_value_from_str("--size")?.unwrap_or(7);
+    let width = args.opt_value_from_str("--width")?;
+    let on = args.opt_value_from_str("--on")?.unwrap_or('#');
+    let off = args.opt_value_from_str("--off")?.unwrap_or('.');
+
+    let mut free_args = args.finish();
+    let text = match free_args.len() {
+        0 => "Hello world".to_string(),
+        1 => free_args
+            .pop()
+            .unwrap()
+            .into_string()
+            .map_err(|_| anyhow!("TEXT is not valid UTF-8"))?,
+        _ => bail!("Unknown arguments. Use '--help' for help on usage."),
+    };
+
+    Ok(Args {
+        show_help,
+        text,
+        size,
+        underline,
+        strikethrough,
+        width,
+        on,
+        off,
+    })
+}
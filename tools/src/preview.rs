@@ -0,0 +1,360 @@
+use std::ffi::{OsStr, OsString};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail};
+use embedded_graphics::{mono_font::MonoTextStyleBuilder, pixelcolor::Rgb888, prelude::*};
+use embedded_graphics_simulator::SimulatorDisplay;
+use pico_args::Arguments;
+
+use crate::render;
+use crate::sizes;
+use crate::style;
+
+const HELP_MESSAGE: &str = "\
+profont preview
+
+Renders text to the terminal using Unicode half-block or braille characters,
+which show 2 or 8 pixels per character.
+
+USAGE:
+    preview [OPTIONS] [TEXT]
+
+The text is taken from TEXT, the file given with --file, or standard input.
+
+FLAGS:
+  -h, --help                Prints help information
+  -s, --strikethrough       Enables strikethrough style
+  -u, --underline           Enables underline style
+      --color               Colour the output with ANSI escape codes. Implied
+                            by --fg, --bg and --decoration.
+
+OPTIONS:
+      --file        FILE    Read the text from FILE
+      --size        POINTS  ProFont size to render with [default: 12]
+      --mode        MODE    half-block or braille [default: half-block]
+      --width       COLS    Wrap the text to fit this many terminal columns
+      --fg          RRGGBB  Text colour [default: FFFFFF]
+      --bg          RRGGBB  Background colour [default: 000000]
+      --decoration  RRGGBB  Underline and strikethrough colour [default: FF6347]
+";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    HalfBlock,
+    Braille,
+}
+
+impl Mode {
+    /// Returns the number of pixels across and down that each terminal character shows.
+    fn cell(self) -> (u32, u32) {
+        match self {
+            Mode::HalfBlock => (1, 2),
+            Mode::Braille => (2, 4),
+        }
+    }
+}
+
+struct Args {
+    show_help: bool,
+    text: Option<String>,
+    file: Option<PathBuf>,
+    size: u8,
+    mode: Mode,
+    underline: bool,
+    strikethrough: bool,
+    width: Option<u32>,
+    color: bool,
+    fg: Rgb888,
+    bg: Rgb888,
+    decoration: Rgb888,
+}
+
+pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
+    let args = parse_args(args)?;
+
+    if args.show_help {
+        println!("{}", HELP_MESSAGE);
+        return Ok(());
+    }
+
+    let text = render::read_text(args.text.clone(), args.file.as_deref())?;
+
+    let font = &sizes::find(args.size)?.font;
+    let mut style = MonoTextStyleBuilder::new()
+        .font(font)
+        .text_color(args.fg)
+        .background_color(args.bg);
+    if args.underline {
+        style = style.underline_with_color(args.decoration);
+    }
+    if args.strikethrough {
+        style = style.strikethrough_with_color(args.decoration);
+    }
+
+    let columns = args
+        .width
+        .map(|width| render::columns_for_width(font, width * args.mode.cell().0));
+    let lines = render::wrap(&text, columns);
+    let display = render::draw_lines(&lines, style.build(), 0, None);
+
+    print!("{}", to_terminal(&display, args.bg, args.mode, args.color));
+
+    Ok(())
+}
+
+/// Converts an image to lines of half-block or braille characters. Pixels that aren't the
+/// background colour are set.
+///
+/// When `color` is true the characters are coloured with 24-bit ANSI escape codes. Half-blocks
+/// show the colour of both pixels, braille characters show the colour of the first set pixel.
+fn to_terminal(display: &SimulatorDisplay<Rgb888>, bg: Rgb888, mode: Mode, color: bool) -> String {
+    let size = display.size();
+    let (cell_width, cell_height) = mode.cell();
+    let pixel = |x: u32, y: u32| {
+        if x < size.width && y < size.height {
+            display.get_pixel(Point::new(x as i32, y as i32))
+        } else {
+            bg
+        }
+    };
+
+    let mut output = String::new();
+    for row in (0..size.height).step_by(cell_height as usize) {
+        // The colours currently set on the terminal
+        let mut current = None;
+        for column in (0..size.width).step_by(cell_width as usize) {
+            let (ch, fg, cell_bg) = match mode {
+                Mode::HalfBlock => {
+                    let (top, bottom) = (pixel(column, row), pixel(column, row + 1));
+                    if color {
+                        // Colour the upper half with the foreground and the lower with the
+                        // background, so no information is lost
+                        ('▀', top, bottom)
+                    } else {
+                        let ch = match (top != bg, bottom != bg) {
+                            (false, false) => ' ',
+                            (true, false) => '▀',
+                            (false, true) => '▄',
+                            (true, true) => '█',
+                        };
+                        (ch, top, bg)
+                    }
+                }
+                Mode::Braille => {
+                    let mut dots = 0;
+                    let mut fg = None;
+                    for (bit, (dx, dy)) in BRAILLE_DOTS.iter().enumerate() {
+                        let color = pixel(column + dx, row + dy);
+                        if color != bg {
+                            dots |= 1 << bit;
+                            fg = fg.or(Some(color));
+                        }
+                    }
+                    let ch = std::char::from_u32(0x2800 + dots).unwrap();
+                    (ch, fg.unwrap_or(bg), bg)
+                }
+            };
+
+            if color && current != Some((fg, cell_bg)) {
+                let _ = write!(
+                    output,
+                    "\x1b[38;2;{};{};{};48;2;{};{};{}m",
+                    fg.r(),
+                    fg.g(),
+                    fg.b(),
+                    cell_bg.r(),
+                    cell_bg.g(),
+                    cell_bg.b()
+                );
+                current = Some((fg, cell_bg));
+            }
+            output.push(ch);
+        }
+        if color {
+            output.push_str("\x1b[0m");
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Offsets of the dots of a braille character, in the order of their bits.
+const BRAILLE_DOTS: [(u32, u32); 8] = [
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+    (0, 3),
+    (1, 3),
+];
+
+fn parse_args(args: Vec<OsString>) -> Result<Args, anyhow::Error> {
+    let mut args = Arguments::from_vec(args);
+
+    let show_help = args.contains(["-h", "--help"]);
+    let underline = args.contains(["-u", "--underline"]);
+    let strikethrough = args.contains(["-s", "--strikethrough"]);
+    let color = args.contains("--color");
+    let file = args.opt_value_from_os_str("--file", parse_path)?;
+    let size = args.opt_value_from_str("--size")?.unwrap_or(12);
+    let mode = args
+        .opt_value_from_fn("--mode", parse_mode)?
+        .unwrap_or(Mode::HalfBlock);
+    let width = args.opt_value_from_str("--width")?;
+    let fg = args.opt_value_from_fn("--fg", style::parse_color)?;
+    let bg = args.opt_value_from_fn("--bg", style::parse_color)?;
+    let decoration = args.opt_value_from_fn("--decoration", style::parse_color)?;
+
+    let mut free_args = args.finish();
+    let text = match free_args.len() {
+        0 => None,
+        1 => Some(
+            free_args
+                .pop()
+                .unwrap()
+                .into_string()
+                .map_err(|_| anyhow!("TEXT is not valid UTF-8"))?,
+        ),
+        _ => bail!("Unknown arguments. Use '--help' for help on usage."),
+    };
+
+    Ok(Args {
+        show_help,
+        text,
+        file,
+        size,
+        mode,
+        underline,
+        strikethrough,
+        width,
+        color: color || fg.is_some() || bg.is_some() || decoration.is_some(),
+        fg: fg.unwrap_or(Rgb888::WHITE),
+        bg: bg.unwrap_or(Rgb888::BLACK),
+        decoration: decoration.unwrap_or(Rgb888::CSS_TOMATO),
+    })
+}
+
+fn parse_mode(s: &str) -> Result<Mode, String> {
+    match s {
+        "half-block" => Ok(Mode::HalfBlock),
+        "braille" => Ok(Mode::Braille),
+        _ => Err(format!(
+            "invalid mode '{}', expected half-block or braille",
+            s
+        )),
+    }
+}
+
+fn parse_path(s: &OsStr) -> Result<PathBuf, &'static str> {
+    Ok(s.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::pixelcolor::Rgb888;
+    use profont::PROFONT_7_POINT;
+
+    use super::*;
+
+    /// Renders "Hi" in 7pt white on black, which is 10x10 pixels:
+    ///
+    /// ```text
+    /// ..........
+    /// ..........
+    /// #..#...#..
+    /// #..#......
+    /// ####..##..
+    /// #..#...#..
+    /// #..#...#..
+    /// #..#..###.
+    /// ..........
+    /// ..........
+    /// ```
+    fn hi() -> SimulatorDisplay<Rgb888> {
+        let style = MonoTextStyleBuilder::new()
+            .font(&PROFONT_7_POINT)
+            .text_color(Rgb888::WHITE)
+            .background_color(Rgb888::BLACK)
+            .build();
+        render::draw_lines(&["Hi".to_string()], style, 0, None)
+    }
+
+    /// A 3x2 display with the top left two pixels red and the bottom right one green.
+    fn colors() -> SimulatorDisplay<Rgb888> {
+        let mut display = SimulatorDisplay::new(Size::new(3, 2));
+        display.clear(Rgb888::BLACK).unwrap();
+        display
+            .draw_iter([
+                Pixel(Point::new(0, 0), Rgb888::RED),
+                Pixel(Point::new(1, 0), Rgb888::RED),
+                Pixel(Point::new(2, 1), Rgb888::GREEN),
+            ])
+            .unwrap();
+        display
+    }
+
+    #[test]
+    fn half_block_hi() {
+        let expected = concat!(
+            "          \n",
+            "█  █   ▀  \n",
+            "█▀▀█  ▀█  \n",
+            "█  █  ▄█▄ \n",
+            "          \n",
+        );
+        assert_eq!(
+            to_terminal(&hi(), Rgb888::BLACK, Mode::HalfBlock, false),
+            expected
+        );
+    }
+
+    #[test]
+    fn braille_hi() {
+        // The last row of characters is padded with the background colour
+        let expected = concat!("⡄⢠⠀⠠⠀\n", "⡏⢹⠀⣹⡀\n", "⠀⠀⠀⠀⠀\n");
+        assert_eq!(
+            to_terminal(&hi(), Rgb888::BLACK, Mode::Braille, false),
+            expected
+        );
+    }
+
+    #[test]
+    fn half_block_color() {
+        // Each half-block shows the top pixel in the foreground and the bottom one in the
+        // background, and the colours are only set when they change
+        let expected = concat!(
+            "\x1b[38;2;255;0;0;48;2;0;0;0m▀▀",
+            "\x1b[38;2;0;0;0;48;2;0;255;0m▀",
+            "\x1b[0m\n",
+        );
+        assert_eq!(
+            to_terminal(&colors(), Rgb888::BLACK, Mode::HalfBlock, true),
+            expected
+        );
+    }
+
+    #[test]
+    fn half_block_without_color() {
+        assert_eq!(
+            to_terminal(&colors(), Rgb888::BLACK, Mode::HalfBlock, false),
+            "▀▀▄\n"
+        );
+    }
+
+    #[test]
+    fn braille_color() {
+        // The character takes the colour of its first set dot
+        let expected = concat!(
+            "\x1b[38;2;255;0;0;48;2;0;0;0m⠉",
+            "\x1b[38;2;0;255;0;48;2;0;0;0m⠂",
+            "\x1b[0m\n",
+        );
+        assert_eq!(
+            to_terminal(&colors(), Rgb888::BLACK, Mode::Braille, true),
+            expected
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use std::ffi::OsString;
+
+use embedded_graphics::{
+    mono_font::MonoFont,
+    pixelcolor::{BinaryColor, Rgb565, Rgb888},
+    prelude::*,
+};
+use embedded_graphics_simulator::{BinaryColorTheme, SimulatorDisplay};
+
+use crate::render;
+use crate::sizes::SIZES;
+
+const HELP_MESSAGE: &str = "\
+profont profiles
+
+Lists the display profiles that 'hello --profile' accepts, along with how many
+columns and rows of text each ProFont size fits on them.
+
+USAGE:
+    profiles [OPTIONS]
+
+FLAGS:
+  -h, --help    Prints help information
+";
+
+/// How a panel shows set and unset pixels.
+pub enum ColorModel {
+    /// Single colour panel, shown with the profile's simulator theme.
+    Monochrome,
+    /// Monochrome OLED with a strip of yellow pixels along the top and blue pixels below it.
+    BlueYellow { yellow_rows: u32 },
+    /// E-paper with black and red ink on white.
+    BlackWhiteRed,
+    /// Colour TFT with 16 bits per pixel.
+    Rgb565,
+}
+
+/// A display panel that ProFont is used on.
+pub struct DisplayProfile {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub size: Size,
+    pub color_model: ColorModel,
+    pub theme: BinaryColorTheme,
+}
+
+pub static PROFILES: [DisplayProfile; 4] = [
+    DisplayProfile {
+        name: "ssd1306",
+        description: "SSD1306 128x64 OLED, yellow and blue",
+        size: Size::new(128, 64),
+        color_model: ColorModel::BlueYellow { yellow_rows: 16 },
+        theme: BinaryColorTheme::Default,
+    },
+    DisplayProfile {
+        name: "inky-phat",
+        description: "Inky pHAT 212x104 e-paper, black, white and red",
+        size: Size::new(212, 104),
+        color_model: ColorModel::BlackWhiteRed,
+        theme: BinaryColorTheme::Default,
+    },
+    DisplayProfile {
+        name: "epaper-2.9",
+        description: "2.9\" 296x128 e-paper, black and white",
+        size: Size::new(296, 128),
+        color_model: ColorModel::Monochrome,
+        theme: BinaryColorTheme::LcdWhite,
+    },
+    DisplayProfile {
+        name: "st7789",
+        description: "ST7789 240x240 IPS TFT, RGB565",
+        size: Size::new(240, 240),
+        color_model: ColorModel::Rgb565,
+        theme: BinaryColorTheme::Default,
+    },
+];
+
+impl DisplayProfile {
+    /// Returns the number of columns and rows of text a font fits on the panel.
+    pub fn text_grid(&self, font: &MonoFont) -> (usize, u32) {
+        (
+            render::columns_for_width(font, self.size.width),
+            self.size.height / font.character_size.height,
+        )
+    }
+
+    /// Converts a binary image, as drawn by a ProFont text style, to the colours the panel shows.
+    ///
+    /// Set pixels in the top `heading_rows` rows are shown in the panel's heading colour. The
+    /// result is meant to be shown with the profile's theme.
+    pub fn colorize(
+        &self,
+        display: &SimulatorDisplay<BinaryColor>,
+        heading_rows: u32,
+    ) -> SimulatorDisplay<Rgb888> {
+        let mut output = SimulatorDisplay::new(display.size());
+        output
+            .draw_iter(display.bounding_box().points().map(|point| {
+                let color = match display.get_pixel(point) {
+                    BinaryColor::On if (point.y as u32) < heading_rows => {
+                        self.color_model.heading(point)
+                    }
+                    BinaryColor::On => self.color_model.foreground(point),
+                    BinaryColor::Off => self.color_model.background(),
+                };
+                Pixel(point, color)
+            }))
+            .unwrap();
+        output
+    }
+}
+
+impl ColorModel {
+    /// The colour of a set pixel at `point`.
+    pub fn foreground(&self, point: Point) -> Rgb888 {
+        match *self {
+            ColorModel::Monochrome => Rgb888::WHITE,
+            ColorModel::BlueYellow { yellow_rows } => {
+                if (point.y as u32) < yellow_rows {
+                    Rgb888::new(0xff, 0xd7, 0x00)
+                } else {
+                    Rgb888::new(0x00, 0xc8, 0xff)
+                }
+            }
+            ColorModel::BlackWhiteRed => Rgb888::BLACK,
+            ColorModel::Rgb565 => Rgb565::WHITE.into(),
+        }
+    }
+
+    /// The colour of a set pixel at `point` in a heading, which is red on panels with red ink.
+    pub fn heading(&self, point: Point) -> Rgb888 {
+        match *self {
+            ColorModel::BlackWhiteRed => Rgb888::RED,
+            _ => self.foreground(point),
+        }
+    }
+
+    /// The colour of an unset pixel.
+    pub fn background(&self) -> Rgb888 {
+        match *self {
+            ColorModel::BlackWhiteRed => Rgb888::WHITE,
+            _ => Rgb888::BLACK,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            ColorModel::Monochrome => "monochrome",
+            ColorModel::BlueYellow { .. } => "blue/yellow",
+            ColorModel::BlackWhiteRed => "black/white/red",
+            ColorModel::Rgb565 => "RGB565",
+        }
+    }
+}
+
+/// Looks up a profile by name.
+pub fn find(name: &str) -> Result<&'static DisplayProfile, String> {
+    PROFILES
+        .iter()
+        .find(|profile| profile.name == name)
+        .ok_or_else(|| {
+            let names = PROFILES
+                .iter()
+                .map(|profile| profile.name)
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("unknown profile '{}'. Available: {}", name, names)
+        })
+}
+
+pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
+    if args.iter().any(|arg| arg.to_str() == Some("--help")) {
+        println!("{}", HELP_MESSAGE);
+        return Ok(());
+    }
+
+    for (i, profile) in PROFILES.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        print_profile(profile);
+    }
+
+    Ok(())
+}
+
+/// Prints a profile's details and the text grid of each size on it.
+pub fn print_profile(profile: &DisplayProfile) {
+    println!("{}: {}", profile.name, profile.description);
+    println!(
+        "  {}x{}, {}",
+        profile.size.width,
+        profile.size.height,
+        profile.color_model.name()
+    );
+    for size in SIZES.iter() {
+        let (columns, rows) = profile.text_grid(&size.font);
+        println!(
+            "  {:>2}pt  {:>3} columns x {:>2} rows",
+            size.point, columns, rows
+        );
+    }
+}
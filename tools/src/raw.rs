@@ -0,0 +1,63 @@
+//! The 1 bit per pixel `.raw` format of the sprite sheets in `font/data`.
+//!
+//! Each row of the sheet starts on a byte boundary, with the leftmost pixel of each byte in its
+//! most significant bit.
+
+use image::{GrayImage, Luma};
+
+/// Packs a sprite sheet into the `.raw` format. Pixels brighter than mid grey are set.
+pub fn pack(sheet: &GrayImage) -> Vec<u8> {
+    let bytes_per_row = (sheet.width() + 7) / 8;
+    let mut raw = vec![0; (bytes_per_row * sheet.height()) as usize];
+    for (x, y, pixel) in sheet.enumerate_pixels() {
+        if pixel.0[0] > 127 {
+            raw[(y * bytes_per_row + x / 8) as usize] |= 0x80 >> (x % 8);
+        }
+    }
+    raw
+}
+
+/// Unpacks a sheet `width` pixels wide from the `.raw` format. Any partial row at the end is
+/// ignored.
+pub fn unpack(raw: &[u8], width: u32) -> GrayImage {
+    let bytes_per_row = ((width + 7) / 8) as usize;
+    let height = (raw.len() / bytes_per_row) as u32;
+    GrayImage::from_fn(width, height, |x, y| {
+        let byte = raw[y as usize * bytes_per_row + x as usize / 8];
+        let set = byte & (0x80 >> (x % 8)) != 0;
+        Luma([if set { 255 } else { 0 }])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_then_unpack_round_trips() {
+        // 13 pixels leaves 3 unused bits at the end of each row
+        let sheet = GrayImage::from_fn(13, 5, |x, y| {
+            Luma([if (x * 7 + y * 3) % 5 < 2 { 255 } else { 0 }])
+        });
+        let raw = pack(&sheet);
+        assert_eq!(raw.len(), 2 * 5);
+        assert_eq!(unpack(&raw, 13), sheet);
+    }
+
+    #[test]
+    fn leftmost_pixel_is_the_most_significant_bit() {
+        let mut sheet = GrayImage::new(9, 2);
+        sheet.put_pixel(0, 0, Luma([255]));
+        sheet.put_pixel(8, 0, Luma([200]));
+        sheet.put_pixel(1, 1, Luma([127]));
+        sheet.put_pixel(7, 1, Luma([128]));
+        assert_eq!(pack(&sheet), [0x80, 0x80, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn unpack_ignores_a_partial_row() {
+        let sheet = unpack(&[0xff, 0x80, 0x00], 9);
+        assert_eq!((sheet.width(), sheet.height()), (9, 1));
+        assert!(sheet.pixels().all(|pixel| pixel.0[0] == 255));
+    }
+}
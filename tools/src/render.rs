@@ -0,0 +1,366 @@
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context};
+use embedded_graphics::{
+    mono_font::{MonoFont, MonoTextStyle, MonoTextStyleBuilder},
+    pixelcolor::{BinaryColor, Rgb888},
+    prelude::*,
+    text::{Baseline, Text},
+};
+use embedded_graphics_simulator::{OutputSettingsBuilder, SimulatorDisplay};
+use image::RgbImage;
+use pico_args::Arguments;
+
+use crate::sizes;
+use crate::style;
+use crate::terminal_graphics::{self, Protocol};
+
+const HELP_MESSAGE: &str = "\
+profont render
+
+Renders text to a PNG, or inline in the terminal, without opening a window.
+
+USAGE:
+    render [OPTIONS] --output FILE [TEXT]
+    render [OPTIONS] --terminal PROTOCOL [TEXT]
+
+The text is taken from TEXT, the file given with --file, or standard input.
+
+FLAGS:
+  -h, --help                Prints help information
+  -s, --strikethrough       Enables strikethrough style
+  -u, --underline           Enables underline style
+
+OPTIONS:
+  -o, --output      FILE    Path of the PNG to write
+      --terminal    PROTOCOL
+                            Print the image to a terminal that supports the
+                            sixel or kitty graphics protocol
+      --file        FILE    Read the text from FILE
+      --size        POINTS  ProFont size to render with [default: 12]
+      --color       RRGGBB  Text and decoration colour [default: 000000]
+      --background  RRGGBB  Background colour [default: FFFFFF]
+      --width       PX      Wrap the text to fit an image of this width
+      --margin      PX      Space around the text [default: 4]
+      --scale       N       Scale the output image by N [default: 1]
+";
+
+struct Args {
+    show_help: bool,
+    output: Option<PathBuf>,
+    terminal: Option<Protocol>,
+    file: Option<PathBuf>,
+    text: Option<String>,
+    size: u8,
+    color: Rgb888,
+    background: Rgb888,
+    underline: bool,
+    strikethrough: bool,
+    width: Option<u32>,
+    margin: u32,
+    scale: u32,
+}
+
+pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
+    let args = parse_args(args)?;
+
+    if args.show_help {
+        println!("{}", HELP_MESSAGE);
+        return Ok(());
+    }
+
+    let text = read_text(args.text.clone(), args.file.as_deref())?;
+
+    let font = &sizes::find(args.size)?.font;
+    let mut style = MonoTextStyleBuilder::new()
+        .font(font)
+        .text_color(args.color)
+        .background_color(args.background);
+    if args.underline {
+        style = style.underline();
+    }
+    if args.strikethrough {
+        style = style.strikethrough();
+    }
+
+    let columns = args
+        .width
+        .map(|width| columns_for_width(font, width.saturating_sub(args.margin * 2)));
+    let lines = wrap(&text, columns);
+    let display = draw_lines(&lines, style.build(), args.margin, args.width);
+    let size = display.size();
+
+    let output_settings = OutputSettingsBuilder::new().scale(args.scale).build();
+    let image = display.to_rgb_output_image(&output_settings);
+    if let Some(ref path) = args.output {
+        image.save_png(path)?;
+        println!(
+            "Wrote {} ({}x{})",
+            path.display(),
+            size.width * args.scale,
+            size.height * args.scale
+        );
+    }
+    if let Some(protocol) = args.terminal {
+        let buffer = image.as_image_buffer();
+        let image = RgbImage::from_raw(buffer.width(), buffer.height(), buffer.to_vec()).unwrap();
+        println!("{}", terminal_graphics::encode(&image, protocol)?);
+    }
+
+    Ok(())
+}
+
+/// Returns TEXT if it was given, otherwise the contents of FILE, otherwise standard input.
+pub fn read_text(text: Option<String>, file: Option<&Path>) -> Result<String, anyhow::Error> {
+    Ok(match (text, file) {
+        (Some(text), _) => text,
+        (None, Some(path)) => fs::read_to_string(path)
+            .with_context(|| format!("unable to read {}", path.display()))?,
+        (None, None) => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            text
+        }
+    })
+}
+
+/// Draws lines of text below each other with space around them, on the style's background colour.
+///
+/// The image is just wide enough for the longest line, unless `width` is given.
+pub fn draw_lines<C>(
+    lines: &[String],
+    style: MonoTextStyle<'_, C>,
+    margin: u32,
+    width: Option<u32>,
+) -> SimulatorDisplay<C>
+where
+    C: PixelColor + From<BinaryColor>,
+{
+    let font = style.font;
+    let text_width = lines
+        .iter()
+        .map(|line| text_width(font, line.chars().count()))
+        .max()
+        .unwrap_or(0);
+    let size = Size::new(
+        width.unwrap_or(text_width + margin * 2),
+        lines.len() as u32 * font.character_size.height + margin * 2,
+    );
+
+    let mut display = SimulatorDisplay::new(size);
+    if let Some(background) = style.background_color {
+        display.clear(background).unwrap();
+    }
+
+    let mut position = Point::new(margin as i32, margin as i32);
+    for line in lines {
+        Text::with_baseline(line, position, style, Baseline::Top)
+            .draw(&mut display)
+            .unwrap();
+        position.y += font.character_size.height as i32;
+    }
+    display
+}
+
+/// Returns the width in pixels of a line of `chars` characters.
+pub fn text_width(font: &MonoFont, chars: usize) -> u32 {
+    (chars as u32 * (font.character_size.width + font.character_spacing))
+        .saturating_sub(font.character_spacing)
+}
+
+/// Returns how many characters fit on a line `width` pixels wide.
+pub fn columns_for_width(font: &MonoFont, width: u32) -> usize {
+    ((width + font.character_spacing) / (font.character_size.width + font.character_spacing))
+        as usize
+}
+
+/// Splits text into lines, wrapping at word boundaries so that no line is longer than `columns`
+/// characters. Words longer than a line are broken.
+pub fn wrap(text: &str, columns: Option<usize>) -> Vec<String> {
+    let columns = match columns {
+        Some(columns) => columns.max(1),
+        None => return text.lines().map(String::from).collect(),
+    };
+
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut line_len = 0;
+        for word in paragraph.split(' ') {
+            let mut word = word.chars().collect::<Vec<_>>();
+            if line_len > 0 && line_len + 1 + word.len() > columns {
+                lines.push(std::mem::take(&mut line));
+                line_len = 0;
+            }
+            if line_len > 0 {
+                line.push(' ');
+                line_len += 1;
+            }
+            while line_len + word.len() > columns {
+                let rest = word.split_off(columns - line_len);
+                line.extend(word);
+                lines.push(std::mem::take(&mut line));
+                line_len = 0;
+                word = rest;
+            }
+            line_len += word.len();
+            line.extend(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+fn parse_args(args: Vec<OsString>) -> Result<Args, anyhow::Error> {
+    let mut args = Arguments::from_vec(args);
+
+    let show_help = args.contains(["-h", "--help"]);
+    if show_help {
+        return Ok(Args {
+            show_help,
+            output: None,
+            terminal: None,
+            file: None,
+            text: None,
+            size: 0,
+            color: Rgb888::BLACK,
+            background: Rgb888::WHITE,
+            underline: false,
+            strikethrough: false,
+            width: None,
+            margin: 0,
+            scale: 1,
+        });
+    }
+
+    let underline = args.contains(["-u", "--underline"]);
+    let strikethrough = args.contains(["-s", "--strikethrough"]);
+    let output = args.opt_value_from_os_str(["-o", "--output"], parse_path)?;
+    let terminal = args.opt_value_from_fn("--terminal", terminal_graphics::parse_protocol)?;
+    if output.is_none() && terminal.is_none() {
+        bail!("Either --output or --terminal is required. Use '--help' for help on usage.");
+    }
+    let file = args.opt_value_from_os_str("--file", parse_path)?;
+    let size = args.opt_value_from_str("--size")?.unwrap_or(12);
+    let color = args
+        .opt_value_from_fn("--color", style::parse_color)?
+        .unwrap_or(Rgb888::BLACK);
+    let background = args
+        .opt_value_from_fn("--background", style::parse_color)?
+        .unwrap_or(Rgb888::WHITE);
+    let width = args.opt_value_from_str("--width")?;
+    let margin = args.opt_value_from_str("--margin")?.unwrap_or(4);
+    let scale = args.opt_value_from_str("--scale")?.unwrap_or(1);
+    if scale == 0 {
+        bail!("--scale must be at least 1");
+    }
+
+    let mut free_args = args.finish();
+    let text = match free_args.len() {
+        0 => None,
+        1 => Some(
+            free_args
+                .pop()
+                .unwrap()
+                .into_string()
+                .map_err(|_| anyhow!("TEXT is not valid UTF-8"))?,
+        ),
+        _ => bail!("Unknown arguments. Use '--help' for help on usage."),
+    };
+
+    Ok(Args {
+        show_help,
+        output,
+        terminal,
+        file,
+        text,
+        size,
+        color,
+        background,
+        underline,
+        strikethrough,
+        width,
+        margin,
+        scale,
+    })
+}
+
+fn parse_path(s: &OsStr) -> Result<PathBuf, &'static str> {
+    Ok(s.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use profont::{PROFONT_12_POINT, PROFONT_14_POINT};
+
+    #[test]
+    fn text_width_leaves_out_trailing_spacing() {
+        assert_eq!(text_width(&PROFONT_14_POINT, 3), 30);
+        assert_eq!(text_width(&PROFONT_12_POINT, 3), 3 * 8 - 1);
+        assert_eq!(text_width(&PROFONT_12_POINT, 1), 7);
+        assert_eq!(text_width(&PROFONT_12_POINT, 0), 0);
+    }
+
+    #[test]
+    fn columns_for_width_without_spacing() {
+        assert_eq!(columns_for_width(&PROFONT_14_POINT, 30), 3);
+        assert_eq!(columns_for_width(&PROFONT_14_POINT, 29), 2);
+    }
+
+    #[test]
+    fn columns_for_width_with_spacing() {
+        // The last character doesn't need spacing after it
+        assert_eq!(columns_for_width(&PROFONT_12_POINT, 23), 3);
+        assert_eq!(columns_for_width(&PROFONT_12_POINT, 22), 2);
+        assert_eq!(columns_for_width(&PROFONT_12_POINT, 7), 1);
+        assert_eq!(columns_for_width(&PROFONT_12_POINT, 6), 0);
+        for chars in 0..20 {
+            let width = text_width(&PROFONT_12_POINT, chars);
+            assert_eq!(columns_for_width(&PROFONT_12_POINT, width), chars);
+        }
+    }
+
+    #[test]
+    fn wrap_without_columns_splits_lines() {
+        assert_eq!(wrap("a long line\nnext", None), ["a long line", "next"]);
+    }
+
+    #[test]
+    fn wrap_at_word_boundaries() {
+        assert_eq!(
+            wrap("the quick brown fox", Some(9)),
+            ["the quick", "brown fox"]
+        );
+        assert_eq!(
+            wrap("the quick brown fox", Some(10)),
+            ["the quick", "brown fox"]
+        );
+        assert_eq!(
+            wrap("the quick brown fox", Some(8)),
+            ["the", "quick", "brown", "fox"]
+        );
+    }
+
+    #[test]
+    fn wrap_breaks_words_longer_than_a_line() {
+        assert_eq!(wrap("abcdefghij", Some(4)), ["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("ab cdefghij", Some(4)), ["ab", "cdef", "ghij"]);
+        assert_eq!(wrap("ab cdefghij k", Some(4)), ["ab", "cdef", "ghij", "k"]);
+        assert_eq!(wrap("αβγδε", Some(2)), ["αβ", "γδ", "ε"]);
+    }
+
+    #[test]
+    fn wrap_keeps_empty_lines() {
+        assert_eq!(wrap("one\n\ntwo", Some(5)), ["one", "", "two"]);
+        assert_eq!(wrap("", Some(5)), Vec::<String>::new());
+    }
+
+    #[test]
+    fn wrap_treats_zero_columns_as_one() {
+        assert_eq!(wrap("ab", Some(0)), ["a", "b"]);
+    }
+}
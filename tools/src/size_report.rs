@@ -0,0 +1,190 @@
+use std::ffi::{OsStr, OsString};
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::bail;
+use embedded_graphics::mono_font::mapping::GlyphMapping;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use image::GrayImage;
+use pico_args::Arguments;
+use profont::GLYPH_MAPPING;
+
+use crate::charset::Charset;
+use crate::generate::{self, CHARS_PER_ROW};
+use crate::glyph_art::Glyph;
+use crate::raw;
+use crate::sizes::{self, FontSize, SIZES};
+
+const HELP_MESSAGE: &str = "\
+profont size-report
+
+Reports the flash used by each PROFONT_*_POINT font: the bytes of its .raw
+image data, the bytes of its glyph mapping string and the total. The same is
+shown for the subset fonts `generate --charset` produces, for ASCII and for
+any charset files given.
+
+The deflated column is the image data compressed with DEFLATE. It has to be
+decompressed into RAM before it can be drawn, so it saves flash at the cost of
+the data size in RAM. It is only shown for subsets of the characters in the
+crate. The MonoFont and StrGlyphMapping structs themselves add a few dozen
+bytes per font, depending on the pointer size of the target.
+
+USAGE:
+    size-report [OPTIONS]
+
+FLAGS:
+  -h, --help                Prints help information
+
+OPTIONS:
+      --size        POINTS  Only report this size
+      --charset     FILE    Also report a subset made from this charset file.
+                            May be given more than once.
+";
+
+struct Args {
+    show_help: bool,
+    size: Option<u8>,
+    charsets: Vec<PathBuf>,
+}
+
+/// The cost of one variant of a font.
+struct Cost {
+    glyphs: usize,
+    data: usize,
+    mapping: usize,
+    deflated: Option<usize>,
+}
+
+pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
+    let args = parse_args(args)?;
+
+    if args.show_help {
+        println!("{}", HELP_MESSAGE);
+        return Ok(());
+    }
+
+    let mut variants = vec![
+        ("latin1 (crate)".to_string(), Charset::latin1()),
+        ("ascii".to_string(), Charset::from_chars(' '..='~')),
+    ];
+    for path in &args.charsets {
+        variants.push((path.display().to_string(), Charset::from_file(path)?));
+    }
+
+    let selected = match args.size {
+        Some(point) => vec![sizes::find(point)?],
+        None => SIZES.iter().collect(),
+    };
+
+    let name_width = variants
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut latin1_data = 0;
+    for size in &selected {
+        let cell = size.font.character_size;
+        println!("{} ({}x{} cells)", size.name(), cell.width, cell.height);
+        println!(
+            "  {:<width$}  {:>6}  {:>7}  {:>7}  {:>7}  {:>8}",
+            "charset",
+            "glyphs",
+            "data",
+            "mapping",
+            "total",
+            "deflated",
+            width = name_width
+        );
+        latin1_data += size.raw.len();
+        for (name, charset) in &variants {
+            let cost = cost(size, charset);
+            let deflated = cost
+                .deflated
+                .map_or_else(|| "-".to_string(), |bytes| bytes.to_string());
+            println!(
+                "  {:<width$}  {:>6}  {:>7}  {:>7}  {:>7}  {:>8}",
+                name,
+                cost.glyphs,
+                cost.data,
+                cost.mapping,
+                cost.data + cost.mapping,
+                deflated,
+                width = name_width
+            );
+        }
+        println!();
+    }
+
+    // Fonts with the same charset share one mapping
+    let mapping = Charset::latin1().glyph_mapping().len();
+    println!(
+        "Sizes shown: {} bytes of data + {} bytes of shared mapping = {} bytes",
+        latin1_data,
+        mapping,
+        latin1_data + mapping
+    );
+
+    Ok(())
+}
+
+/// Works out the cost of `size` made from the characters in `charset`, laid out in a sprite sheet
+/// the way `generate` does.
+fn cost(size: &FontSize, charset: &Charset) -> Cost {
+    let cell = size.font.character_size;
+    let sheet_width = CHARS_PER_ROW as u32 * cell.width;
+    let rows = (charset.len() + CHARS_PER_ROW as usize - 1) / CHARS_PER_ROW as usize;
+    let bytes_per_row = ((sheet_width + 7) / 8) as usize;
+    let data = rows * cell.height as usize * bytes_per_row;
+
+    // Compressing needs the glyphs, which are only available for characters in the crate
+    let deflated = if charset.chars().iter().all(|&ch| GLYPH_MAPPING.contains(ch)) {
+        let source = raw::unpack(size.raw, sheet_width);
+        let mut sheet = GrayImage::new(sheet_width, rows as u32 * cell.height);
+        for (index, &ch) in charset.chars().iter().enumerate() {
+            let (x, y) = generate::cell_origin(GLYPH_MAPPING.index(ch), cell.width, cell.height);
+            let glyph = Glyph::from_sheet(ch, &source, x, y, cell.width, cell.height);
+            let (x, y) = generate::cell_origin(index, cell.width, cell.height);
+            glyph.to_sheet(&mut sheet, x, y);
+        }
+        Some(deflated_len(&raw::pack(&sheet)))
+    } else {
+        None
+    };
+
+    Cost {
+        glyphs: charset.len(),
+        data,
+        mapping: charset.glyph_mapping().len(),
+        deflated,
+    }
+}
+
+fn deflated_len(data: &[u8]) -> usize {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    // Writing to a Vec can't fail
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap().len()
+}
+
+fn parse_args(args: Vec<OsString>) -> Result<Args, anyhow::Error> {
+    let mut args = Arguments::from_vec(args);
+
+    let show_help = args.contains(["-h", "--help"]);
+    let size = args.opt_value_from_str("--size")?;
+    let charsets = args.values_from_os_str("--charset", parse_path)?;
+
+    if !args.finish().is_empty() {
+        bail!("Unknown arguments. Use '--help' for help on usage.");
+    }
+
+    Ok(Args {
+        show_help,
+        size,
+        charsets,
+    })
+}
+
+fn parse_path(s: &OsStr) -> Result<PathBuf, &'static str> {
+    Ok(s.into())
+}
@@ -0,0 +1,81 @@
+use anyhow::anyhow;
+use embedded_graphics::mono_font::MonoFont;
+use profont::*;
+
+/// One of the sizes provided by the `profont` crate.
+pub struct FontSize {
+    /// Point size, as used in the name of the `PROFONT_*_POINT` constant.
+    pub point: u8,
+    /// Size of the strike in the source font the size is generated from.
+    pub strike: u8,
+    pub font: MonoFont<'static>,
+    /// Contents of the `.raw` file the font image is made from.
+    pub raw: &'static [u8],
+}
+
+impl FontSize {
+    /// Returns the name used for the data files of this size, e.g. `ProFont7Point`.
+    pub fn name(&self) -> String {
+        format!("ProFont{}Point", self.point)
+    }
+}
+
+pub static SIZES: [FontSize; 7] = [
+    FontSize {
+        point: 7,
+        strike: 9,
+        font: PROFONT_7_POINT,
+        raw: include_bytes!("../../font/data/ProFont7Point.raw"),
+    },
+    FontSize {
+        point: 9,
+        strike: 10,
+        font: PROFONT_9_POINT,
+        raw: include_bytes!("../../font/data/ProFont9Point.raw"),
+    },
+    FontSize {
+        point: 10,
+        strike: 11,
+        font: PROFONT_10_POINT,
+        raw: include_bytes!("../../font/data/ProFont10Point.raw"),
+    },
+    FontSize {
+        point: 12,
+        strike: 14,
+        font: PROFONT_12_POINT,
+        raw: include_bytes!("../../font/data/ProFont12Point.raw"),
+    },
+    FontSize {
+        point: 14,
+        strike: 16,
+        font: PROFONT_14_POINT,
+        raw: include_bytes!("../../font/data/ProFont14Point.raw"),
+    },
+    FontSize {
+        point: 18,
+        strike: 21,
+        font: PROFONT_18_POINT,
+        raw: include_bytes!("../../font/data/ProFont18Point.raw"),
+    },
+    FontSize {
+        point: 24,
+        strike: 28,
+        font: PROFONT_24_POINT,
+        raw: include_bytes!("../../font/data/ProFont24Point.raw"),
+    },
+];
+
+/// Looks up a size by its point size.
+pub fn find(point: u8) -> Result<&'static FontSize, anyhow::Error> {
+    SIZES
+        .iter()
+        .find(|size| size.point == point)
+        .ok_or_else(|| {
+            let points = SIZES
+                .iter()
+                .map(|size| size.point.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            anyhow!("There is no {} point size. Available: {}", point, points)
+        })
+}
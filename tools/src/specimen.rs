@@ -0,0 +1,327 @@
+use std::ffi::{OsStr, OsString};
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{bail, Context};
+use embedded_graphics::{
+    mono_font::{mapping::GlyphMapping, DecorationDimensions, MonoTextStyle},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use embedded_graphics_simulator::SimulatorDisplay;
+use pico_args::Arguments;
+use profont::GLYPH_MAPPING;
+
+use crate::render;
+use crate::sizes::{FontSize, SIZES};
+
+const HELP_MESSAGE: &str = "\
+profont specimen
+
+Writes a self-contained HTML specimen page showing every size, each glyph with
+its code point, the cell metrics, gaps in the character coverage and sample
+paragraphs. Glyphs are drawn from the font data in the crate as SVG pixel
+paths, so the page always matches the shipped .raw files.
+
+USAGE:
+    specimen [OPTIONS]
+
+FLAGS:
+  -h, --help                Prints help information
+
+OPTIONS:
+  -o, --output      FILE    Path of the HTML file to write [default: specimen.html]
+      --scale       N       Size of each font pixel on the page [default: 3]
+";
+const PARAGRAPHS: &[&str] = &[
+    "ProFont is a monospaced font made for programming. It has clear, distinct \
+     forms for easily confused characters like 0O, 1lI|, and ({[]}).",
+    "fn main() {\n    let answer = 6 * 7;\n    println!(\"{} != {:#x}\", answer, 0xBEEF);\n}",
+    "Größe, Façade, Ærø, Ça va? «Déjà vu» - £12.50, ¥300, §4 ¶2, ½ + ¼ = ¾, 45°±2",
+];
+/// Columns the sample paragraphs are wrapped to.
+const PARAGRAPH_COLUMNS: usize = 64;
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; background: #fafafa; color: #222; }
+h1 { margin-bottom: 0.2em; }
+h2 { margin-top: 2em; border-bottom: 1px solid #ccc; }
+table { border-collapse: collapse; }
+td, th { padding: 0.2em 0.8em; text-align: left; border-bottom: 1px solid #e4e4e4; }
+code { font-size: 0.9em; }
+svg.pixels { display: block; image-rendering: pixelated; }
+svg.pixels path { fill: #111; }
+.sample { background: #fff; padding: 1em; margin: 1em 0; border: 1px solid #e4e4e4; }
+.sample svg { max-width: 100%; height: auto; }
+.glyphs { display: flex; flex-wrap: wrap; gap: 6px; }
+.glyph { margin: 0; background: #fff; border: 1px solid #e4e4e4; padding: 4px; }
+.glyph svg { background: #f0f0f0; }
+.glyph figcaption { font-size: 0.7em; color: #666; text-align: center; margin-top: 2px; }
+";
+
+struct Args {
+    show_help: bool,
+    output: PathBuf,
+    scale: u32,
+}
+
+pub fn main(args: Vec<OsString>) -> Result<(), anyhow::Error> {
+    let args = parse_args(args)?;
+
+    if args.show_help {
+        println!("{}", HELP_MESSAGE);
+        return Ok(());
+    }
+
+    let html = specimen(args.scale);
+    fs::write(&args.output, html)
+        .with_context(|| format!("unable to write {}", args.output.display()))?;
+    println!("Wrote {}", args.output.display());
+
+    Ok(())
+}
+
+/// Builds the specimen page.
+fn specimen(scale: u32) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>ProFont specimen</title>\n");
+    let _ = writeln!(html, "<style>{}</style>", STYLE);
+    html.push_str("</head>\n<body>\n<h1>ProFont</h1>\n");
+    let _ = writeln!(
+        html,
+        "<p>{} sizes, {} glyphs each.</p>",
+        SIZES.len(),
+        GLYPH_MAPPING.chars().count()
+    );
+
+    html.push_str("<ul>\n");
+    for size in SIZES.iter() {
+        let _ = writeln!(
+            html,
+            "<li><a href=\"#{}\">{}pt</a></li>",
+            size.name(),
+            size.point
+        );
+    }
+    html.push_str("</ul>\n");
+
+    write_coverage(&mut html);
+    for size in SIZES.iter() {
+        write_size(&mut html, size, scale);
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Describes the ranges of characters the font covers, and the gaps between them.
+fn write_coverage(html: &mut String) {
+    let ranges = char_ranges(GLYPH_MAPPING.chars());
+    html.push_str("<h2 id=\"coverage\">Coverage</h2>\n<table>\n");
+    html.push_str("<tr><th>Covered</th><th>Characters</th></tr>\n");
+    for &(start, end) in &ranges {
+        let _ = writeln!(
+            html,
+            "<tr><td><code>{}</code></td><td>{}</td></tr>",
+            describe_range(start, end),
+            end as u32 - start as u32 + 1
+        );
+    }
+    html.push_str("</table>\n");
+
+    let gaps = ranges
+        .windows(2)
+        .map(|pair| {
+            let start = std::char::from_u32(pair[0].1 as u32 + 1).unwrap_or(pair[0].1);
+            let end = std::char::from_u32(pair[1].0 as u32 - 1).unwrap_or(pair[1].0);
+            (start, end)
+        })
+        .collect::<Vec<_>>();
+    if gaps.is_empty() {
+        html.push_str("<p>There are no gaps in the coverage.</p>\n");
+    } else {
+        html.push_str("<p>Gaps, which are drawn with the fallback glyph:</p>\n<ul>\n");
+        for (start, end) in gaps {
+            let _ = writeln!(html, "<li><code>{}</code></li>", describe_range(start, end));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    let fallback = GLYPH_MAPPING
+        .chars()
+        .nth(GLYPH_MAPPING.index('\u{ffff}'))
+        .unwrap_or('?');
+    let _ = writeln!(
+        html,
+        "<p>Fallback glyph: <code>U+{:04X}</code> {}</p>",
+        fallback as u32,
+        escape(&fallback.to_string())
+    );
+}
+
+fn write_size(html: &mut String, size: &FontSize, scale: u32) {
+    let font = &size.font;
+    let cell = font.character_size;
+
+    let _ = writeln!(html, "<h2 id=\"{}\">{}pt</h2>", size.name(), size.point);
+    html.push_str("<table>\n");
+    let metrics = [
+        (
+            "Constant",
+            format!("<code>PROFONT_{}_POINT</code>", size.point),
+        ),
+        ("Cell", format!("{} &times; {} px", cell.width, cell.height)),
+        (
+            "Character spacing",
+            format!("{} px", font.character_spacing),
+        ),
+        ("Baseline", format!("row {}", font.baseline)),
+        ("Underline", describe_decoration(&font.underline)),
+        ("Strikethrough", describe_decoration(&font.strikethrough)),
+        ("Source strike", format!("{} px", size.strike)),
+        ("Data", format!("{} bytes", size.raw.len())),
+    ];
+    for (name, value) in metrics.iter() {
+        let _ = writeln!(html, "<tr><th>{}</th><td>{}</td></tr>", name, value);
+    }
+    html.push_str("</table>\n");
+
+    for paragraph in PARAGRAPHS {
+        let lines = render::wrap(paragraph, Some(PARAGRAPH_COLUMNS));
+        let style = MonoTextStyle::new(font, BinaryColor::On);
+        let display = render::draw_lines(&lines, style, 0, None);
+        let _ = writeln!(
+            html,
+            "<div class=\"sample\">{}</div>",
+            svg(&display, scale, paragraph)
+        );
+    }
+
+    html.push_str("<div class=\"glyphs\">\n");
+    for ch in GLYPH_MAPPING.chars() {
+        let mut display = SimulatorDisplay::<BinaryColor>::new(cell);
+        let mut text = [0; 4];
+        Text::with_baseline(
+            ch.encode_utf8(&mut text),
+            Point::zero(),
+            MonoTextStyle::new(font, BinaryColor::On),
+            Baseline::Top,
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        let _ = writeln!(
+            html,
+            "<figure class=\"glyph\">{}<figcaption>U+{:04X}</figcaption></figure>",
+            svg(&display, scale, &ch.to_string()),
+            ch as u32
+        );
+    }
+    html.push_str("</div>\n");
+}
+
+/// Draws the set pixels of an image as a single SVG path, with a rectangle per run of pixels in
+/// each row so that edges stay crisp at any scale.
+fn svg(display: &SimulatorDisplay<BinaryColor>, scale: u32, label: &str) -> String {
+    let size = display.size();
+    let mut path = String::new();
+    for y in 0..size.height as i32 {
+        let mut x = 0;
+        while x < size.width as i32 {
+            let is_set = |x| display.get_pixel(Point::new(x, y)) == BinaryColor::On;
+            if !is_set(x) {
+                x += 1;
+                continue;
+            }
+            let start = x;
+            while x < size.width as i32 && is_set(x) {
+                x += 1;
+            }
+            let _ = write!(path, "M{} {}h{}v1h-{}z", start, y, x - start, x - start);
+        }
+    }
+
+    format!(
+        "<svg class=\"pixels\" width=\"{}\" height=\"{}\" \
+         viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\" role=\"img\" aria-label=\"{}\">\
+         <path d=\"{}\"/></svg>",
+        size.width * scale,
+        size.height * scale,
+        size.width,
+        size.height,
+        escape(label),
+        path
+    )
+}
+
+/// Groups sorted characters into ranges of consecutive code points.
+fn char_ranges(chars: impl Iterator<Item = char>) -> Vec<(char, char)> {
+    let mut ranges: Vec<(char, char)> = Vec::new();
+    for ch in chars {
+        match ranges.last_mut() {
+            Some((_, end)) if *end as u32 + 1 == ch as u32 => *end = ch,
+            _ => ranges.push((ch, ch)),
+        }
+    }
+    ranges
+}
+
+fn describe_range(start: char, end: char) -> String {
+    if start == end {
+        format!("U+{:04X}", start as u32)
+    } else {
+        format!("U+{:04X}&ndash;U+{:04X}", start as u32, end as u32)
+    }
+}
+
+fn describe_decoration(decoration: &DecorationDimensions) -> String {
+    match decoration.height {
+        1 => format!("row {}", decoration.offset),
+        height => format!(
+            "rows {}&ndash;{}",
+            decoration.offset,
+            decoration.offset + height - 1
+        ),
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push(' '),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn parse_args(args: Vec<OsString>) -> Result<Args, anyhow::Error> {
+    let mut args = Arguments::from_vec(args);
+
+    let show_help = args.contains(["-h", "--help"]);
+    let output = args
+        .opt_value_from_os_str(["-o", "--output"], parse_path)?
+        .unwrap_or_else(|| PathBuf::from("specimen.html"));
+    let scale = args.opt_value_from_str("--scale")?.unwrap_or(3);
+
+    if !args.finish().is_empty() {
+        bail!("Unknown arguments. Use '--help' for help on usage.");
+    }
+
+    Ok(Args {
+        show_help,
+        output,
+        scale,
+    })
+}
+
+fn parse_path(s: &OsStr) -> Result<PathBuf, &'static str> {
+    Ok(s.into())
+}
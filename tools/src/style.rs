@@ -30,7 +30,7 @@ pub fn parse_args(args: Vec<OsString>) -> Result<(StyleArgs, Vec<OsString>), pic
     ))
 }
 
-pub fn builder(args: &StyleArgs) -> MonoTextStyleBuilder<Rgb888> {
+pub fn builder<'a>(args: &StyleArgs) -> MonoTextStyleBuilder<'a, Rgb888> {
     let mut style = MonoTextStyleBuilder::new().text_color(Rgb888::WHITE);
 
     if args.strikethrough {
@@ -43,7 +43,7 @@ pub fn builder(args: &StyleArgs) -> MonoTextStyleBuilder<Rgb888> {
     style
 }
 
-pub fn binary_builder(args: &StyleArgs) -> MonoTextStyleBuilder<BinaryColor> {
+pub fn binary_builder<'a>(args: &StyleArgs) -> MonoTextStyleBuilder<'a, BinaryColor> {
     let mut style = MonoTextStyleBuilder::new().text_color(BinaryColor::On);
 
     if args.strikethrough {
@@ -56,6 +56,17 @@ pub fn binary_builder(args: &StyleArgs) -> MonoTextStyleBuilder<BinaryColor> {
     style
 }
 
+/// Parses a colour written as `RRGGBB` in hex, optionally prefixed with `#`.
+pub fn parse_color(s: &str) -> Result<Rgb888, String> {
+    let hex = s.strip_prefix('#').unwrap_or(s);
+    match u32::from_str_radix(hex, 16) {
+        Ok(rgb) if hex.len() == 6 => {
+            Ok(Rgb888::new((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+        }
+        _ => Err(format!("invalid colour '{}', expected RRGGBB", s)),
+    }
+}
+
 pub fn help(command_name: &str, description: &str) -> String {
     format!(
         "\
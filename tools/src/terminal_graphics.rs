@@ -0,0 +1,220 @@
+//! Inline images for terminals that support the sixel or kitty graphics protocols.
+
+use std::fmt::Write;
+
+use anyhow::bail;
+use image::RgbImage;
+
+/// Largest amount of base64 data the kitty protocol allows in one escape sequence.
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Protocol {
+    Sixel,
+    Kitty,
+}
+
+pub fn parse_protocol(s: &str) -> Result<Protocol, String> {
+    match s {
+        "sixel" => Ok(Protocol::Sixel),
+        "kitty" => Ok(Protocol::Kitty),
+        _ => Err(format!(
+            "invalid terminal protocol '{}', expected sixel or kitty",
+            s
+        )),
+    }
+}
+
+/// Encodes an image as the escape sequences of a graphics protocol.
+pub fn encode(image: &RgbImage, protocol: Protocol) -> Result<String, anyhow::Error> {
+    match protocol {
+        Protocol::Sixel => sixel(image),
+        Protocol::Kitty => Ok(kitty(image)),
+    }
+}
+
+/// Encodes an image as sixels, with a palette of the colours in the image.
+///
+/// Sixel palettes are limited to 256 colours, which is plenty for rendered text.
+pub fn sixel(image: &RgbImage) -> Result<String, anyhow::Error> {
+    let mut palette: Vec<[u8; 3]> = Vec::new();
+    for pixel in image.pixels() {
+        if !palette.contains(&pixel.0) {
+            palette.push(pixel.0);
+        }
+    }
+    if palette.len() > 256 {
+        bail!(
+            "image has {} colours, sixels support at most 256",
+            palette.len()
+        );
+    }
+
+    // Start the sequence with 1:1 pixels and the image size, then define the palette. Sixel
+    // colours are given in percent.
+    let mut output = format!("\x1bPq\"1;1;{};{}", image.width(), image.height());
+    for (i, [r, g, b]) in palette.iter().enumerate() {
+        let percent = |c: u8| (u32::from(c) * 100 + 127) / 255;
+        let _ = write!(
+            output,
+            "#{};2;{};{};{}",
+            i,
+            percent(*r),
+            percent(*g),
+            percent(*b)
+        );
+    }
+
+    // Each band is 6 rows high and is drawn once per colour, returning to the start of the band
+    // with '$' in between
+    for band_top in (0..image.height()).step_by(6) {
+        let band_height = (image.height() - band_top).min(6);
+        let mut first = true;
+        for (i, color) in palette.iter().enumerate() {
+            let sixels = (0..image.width())
+                .map(|x| {
+                    (0..band_height)
+                        .filter(|&dy| image.get_pixel(x, band_top + dy).0 == *color)
+                        .fold(0, |bits, dy| bits | 1 << dy)
+                })
+                .collect::<Vec<u8>>();
+            if sixels.iter().all(|&bits| bits == 0) {
+                continue;
+            }
+
+            if !first {
+                output.push('$');
+            }
+            first = false;
+            let _ = write!(output, "#{}", i);
+            push_sixel_runs(&mut output, &sixels);
+        }
+        output.push('-');
+    }
+
+    output.push_str("\x1b\\");
+    Ok(output)
+}
+
+/// Writes sixel characters, run length encoding repeats with `!count`.
+fn push_sixel_runs(output: &mut String, sixels: &[u8]) {
+    let mut i = 0;
+    while i < sixels.len() {
+        let bits = sixels[i];
+        let run = sixels[i..]
+            .iter()
+            .take_while(|&&other| other == bits)
+            .count();
+        let ch = char::from(63 + bits);
+        if run > 3 {
+            let _ = write!(output, "!{}{}", run, ch);
+        } else {
+            output.extend(std::iter::repeat(ch).take(run));
+        }
+        i += run;
+    }
+}
+
+/// Encodes an image with the kitty graphics protocol as 24-bit RGB data, split into chunks.
+pub fn kitty(image: &RgbImage) -> String {
+    let data = base64::encode(image.as_raw());
+    let chunks = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect::<Vec<_>>();
+
+    let mut output = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        output.push_str("\x1b_G");
+        if i == 0 {
+            // Transmit and display the image straight away
+            let _ = write!(output, "a=T,f=24,s={},v={},", image.width(), image.height());
+        }
+        let _ = write!(output, "m={};", more);
+        // Base64 is ASCII, so the chunks split on character boundaries
+        output.push_str(std::str::from_utf8(chunk).unwrap());
+        output.push_str("\x1b\\");
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::{mono_font::MonoTextStyleBuilder, pixelcolor::Rgb888, prelude::*};
+    use embedded_graphics_simulator::OutputSettings;
+    use profont::PROFONT_7_POINT;
+
+    use super::*;
+    use crate::render;
+
+    /// Renders "Hi" in 7pt black on white, which is 10x10 pixels.
+    fn hi() -> RgbImage {
+        let style = MonoTextStyleBuilder::new()
+            .font(&PROFONT_7_POINT)
+            .text_color(Rgb888::BLACK)
+            .background_color(Rgb888::WHITE)
+            .build();
+        let display = render::draw_lines(&["Hi".to_string()], style, 0, None);
+        let image = display.to_rgb_output_image(&OutputSettings::default());
+        let buffer = image.as_image_buffer();
+        RgbImage::from_raw(buffer.width(), buffer.height(), buffer.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn sixel_hi() {
+        let expected = concat!(
+            "\x1bPq\"1;1;10;10",
+            "#0;2;100;100;100#1;2;0;0;0",
+            // Rows 0-5, white then black. The black sixels decode to:
+            //   ..........
+            //   ..........
+            //   #..#...#..
+            //   #..#......
+            //   ####..##..
+            //   #..#...#..
+            "#0BnnB~~nJ~~$#1{OO{??Os??-",
+            // Rows 6-9, where the letters end two rows above the bottom of the cell:
+            //   #..#...#..
+            //   #..#..###.
+            //   ..........
+            //   ..........
+            "#0KNNKNNLKLN$#1B??B??ABA?-",
+            "\x1b\\"
+        );
+        assert_eq!(sixel(&hi()).unwrap(), expected);
+    }
+
+    #[test]
+    fn sixel_run_length_encodes_repeats() {
+        let mut output = String::new();
+        push_sixel_runs(&mut output, &[0, 0, 0, 0, 0, 1, 1, 1]);
+        assert_eq!(output, "!5?@@@");
+    }
+
+    #[test]
+    fn kitty_hi() {
+        let image = hi();
+        let output = kitty(&image);
+        let data = base64::encode(image.as_raw());
+        assert_eq!(data.len(), 400);
+        assert_eq!(
+            output,
+            format!("\x1b_Ga=T,f=24,s=10,v=10,m=0;{}\x1b\\", data)
+        );
+    }
+
+    #[test]
+    fn kitty_splits_large_images_into_chunks() {
+        let image = RgbImage::new(64, 64);
+        let output = kitty(&image);
+        let data = base64::encode(image.as_raw());
+        let sequences = output.split("\x1b\\").collect::<Vec<_>>();
+        // 12288 bytes of RGB data is 16384 bytes of base64, which is exactly 4 chunks
+        assert_eq!(sequences.len(), 5);
+        assert_eq!(sequences[4], "");
+        assert_eq!(
+            sequences[0],
+            format!("\x1b_Ga=T,f=24,s=64,v=64,m=1;{}", &data[..4096])
+        );
+        assert_eq!(sequences[1], format!("\x1b_Gm=1;{}", &data[4096..8192]));
+        assert_eq!(sequences[3], format!("\x1b_Gm=0;{}", &data[12288..]));
+    }
+}